@@ -0,0 +1,757 @@
+//! Atlassian Document Format, the rich text representation used by Jira
+//! Cloud's API v3 for descriptions and comments.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Node {
+    pub r#type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub marks: Vec<Mark>,
+
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub attrs: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Mark {
+    pub r#type: String,
+
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub attrs: Map<String, Value>,
+}
+
+impl Node {
+    pub fn doc(content: Vec<Node>) -> Node {
+        Node {
+            r#type: "doc".to_owned(),
+            version: Some(1),
+            content,
+            ..Default::default()
+        }
+    }
+
+    fn block(r#type: &str, content: Vec<Node>) -> Node {
+        Node {
+            r#type: r#type.to_owned(),
+            content,
+            ..Default::default()
+        }
+    }
+
+    fn text(text: &str, marks: Vec<Mark>) -> Node {
+        Node {
+            r#type: "text".to_owned(),
+            text: Some(text.to_owned()),
+            marks,
+            ..Default::default()
+        }
+    }
+
+    fn hard_break() -> Node {
+        Node::block("hardBreak", vec![])
+    }
+
+    fn with_attr<V: Into<Value>>(mut self, key: &str, value: V) -> Node {
+        self.attrs.insert(key.to_owned(), value.into());
+        self
+    }
+
+    fn attr_str(&self, key: &str) -> Option<&str> {
+        self.attrs.get(key).and_then(Value::as_str)
+    }
+
+    fn attr_u64(&self, key: &str) -> Option<u64> {
+        self.attrs.get(key).and_then(Value::as_u64)
+    }
+
+    /// Renders the document as markdown: emphasis, code, links, headings,
+    /// lists, quotes and code blocks are kept, anything else is reduced to its text.
+    pub fn to_markdown(&self) -> String {
+        blocks(&self.content, Flavor::Markdown)
+    }
+
+    pub fn to_plain_text(&self) -> String {
+        blocks(&self.content, Flavor::PlainText)
+    }
+
+    /// Parses the markdown subset produced by `to_markdown`.
+    ///
+    /// Single line breaks inside a paragraph become hard breaks, as Jira users expect.
+    pub fn from_markdown(text: &str) -> Node {
+        let lines = text.lines().collect::<Vec<_>>();
+        Node::doc(parse_blocks(&lines))
+    }
+
+    /// Wraps text into paragraphs without interpreting any markup.
+    pub fn from_plain_text(text: &str) -> Node {
+        let mut paragraphs = vec![];
+        let mut lines: Vec<&str> = vec![];
+
+        for line in text.lines().chain(std::iter::once("")) {
+            if !line.trim().is_empty() {
+                lines.push(line);
+                continue;
+            }
+
+            if !lines.is_empty() {
+                let mut content = vec![];
+
+                for (index, line) in lines.drain(..).enumerate() {
+                    if index > 0 {
+                        content.push(Node::hard_break());
+                    }
+                    content.push(Node::text(line, vec![]));
+                }
+
+                paragraphs.push(Node::block("paragraph", content));
+            }
+        }
+
+        Node::doc(paragraphs)
+    }
+}
+
+/// How the client turns text into a document for Jira Cloud.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextFormat {
+    /// Interprets the markdown subset of `Node::from_markdown`.
+    #[default]
+    Markdown,
+    /// Sends the text as is, see `Node::from_plain_text`.
+    PlainText,
+}
+
+impl TextFormat {
+    pub fn to_document(self, text: &str) -> Node {
+        match self {
+            TextFormat::Markdown => Node::from_markdown(text),
+            TextFormat::PlainText => Node::from_plain_text(text),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Flavor {
+    Markdown,
+    PlainText,
+}
+
+fn blocks(nodes: &[Node], flavor: Flavor) -> String {
+    nodes
+        .iter()
+        .map(|node| block(node, flavor))
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn block(node: &Node, flavor: Flavor) -> String {
+    let markdown = flavor == Flavor::Markdown;
+
+    match node.r#type.as_str() {
+        "paragraph" => inline(&node.content, flavor),
+        "heading" => {
+            let text = inline(&node.content, flavor);
+
+            if markdown {
+                let level = node.attr_u64("level").unwrap_or(1).clamp(1, 6) as usize;
+                format!("{} {}", "#".repeat(level), text)
+            } else {
+                text
+            }
+        }
+        "bulletList" => list(node, flavor, |_| "- ".to_owned()),
+        "orderedList" => {
+            let start = node.attr_u64("order").unwrap_or(1);
+            list(node, flavor, |index| format!("{}. ", start + index as u64))
+        }
+        "codeBlock" => {
+            let code = inline(&node.content, Flavor::PlainText);
+
+            if markdown {
+                let language = node.attr_str("language").unwrap_or_default();
+                format!("```{}\n{}\n```", language, code)
+            } else {
+                code
+            }
+        }
+        "blockquote" => {
+            let quote = blocks(&node.content, flavor);
+
+            if markdown {
+                quote
+                    .lines()
+                    .map(|line| format!("> {}", line).trim_end().to_owned())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                quote
+            }
+        }
+        "rule" => {
+            if markdown {
+                "---".to_owned()
+            } else {
+                String::new()
+            }
+        }
+        "table" => node
+            .content
+            .iter()
+            .map(|row| {
+                let cells = row
+                    .content
+                    .iter()
+                    .map(|cell| blocks(&cell.content, flavor).replace('\n', " "))
+                    .collect::<Vec<_>>();
+
+                if markdown {
+                    format!("| {} |", cells.join(" | "))
+                } else {
+                    cells.join("\t")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "media" | "mediaGroup" => String::new(),
+        "text" | "hardBreak" | "mention" | "emoji" | "inlineCard" | "status" | "date" => {
+            inline(std::slice::from_ref(node), flavor)
+        }
+        _ => blocks(&node.content, flavor),
+    }
+}
+
+fn list<M: Fn(usize) -> String>(node: &Node, flavor: Flavor, marker: M) -> String {
+    node.content
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let marker = marker(index);
+            let indent = " ".repeat(marker.len());
+
+            let body = item
+                .content
+                .iter()
+                .map(|node| block(node, flavor))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            body.lines()
+                .enumerate()
+                .map(|(line_index, line)| {
+                    if line_index == 0 {
+                        format!("{}{}", marker, line)
+                    } else if line.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{}{}", indent, line)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn inline(nodes: &[Node], flavor: Flavor) -> String {
+    let mut output = String::new();
+
+    for node in nodes {
+        match node.r#type.as_str() {
+            "text" => {
+                let text = node.text.as_deref().unwrap_or_default();
+
+                if flavor == Flavor::Markdown {
+                    output.push_str(&marked(text, &node.marks));
+                } else {
+                    output.push_str(text);
+                }
+            }
+            "hardBreak" => output.push('\n'),
+            "mention" => match node.attr_str("text") {
+                Some(text) => output.push_str(text),
+                None => output.push_str(&format!("@{}", node.attr_str("id").unwrap_or_default())),
+            },
+            "emoji" => output.push_str(
+                node.attr_str("text")
+                    .or_else(|| node.attr_str("shortName"))
+                    .unwrap_or_default(),
+            ),
+            "inlineCard" => output.push_str(node.attr_str("url").unwrap_or_default()),
+            "status" => output.push_str(node.attr_str("text").unwrap_or_default()),
+            "date" => {
+                let date = node
+                    .attr_str("timestamp")
+                    .and_then(|timestamp| timestamp.parse().ok())
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .map(|date| date.format("%Y-%m-%d").to_string());
+
+                output.push_str(&date.unwrap_or_default());
+            }
+            _ => output.push_str(&inline(&node.content, flavor)),
+        }
+    }
+
+    output
+}
+
+fn marked(text: &str, marks: &[Mark]) -> String {
+    let has = |r#type: &str| marks.iter().any(|mark| mark.r#type == r#type);
+
+    let mut text = text.to_owned();
+
+    if has("code") {
+        text = format!("`{}`", text);
+    }
+    if has("em") {
+        text = format!("*{}*", text);
+    }
+    if has("strong") {
+        text = format!("**{}**", text);
+    }
+    if has("strike") {
+        text = format!("~~{}~~", text);
+    }
+
+    let href = marks
+        .iter()
+        .find(|mark| mark.r#type == "link")
+        .and_then(|mark| mark.attrs.get("href"))
+        .and_then(Value::as_str);
+
+    if let Some(href) = href {
+        text = format!("[{}]({})", text, href);
+    }
+
+    text
+}
+
+fn parse_blocks(lines: &[&str]) -> Vec<Node> {
+    let mut nodes = vec![];
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            index += 1;
+        } else if let Some(language) = trimmed.strip_prefix("```") {
+            let code_lines = lines[index + 1..]
+                .iter()
+                .take_while(|line| !line.trim_start().starts_with("```"))
+                .copied()
+                .collect::<Vec<_>>();
+            index += code_lines.len() + 2;
+
+            let mut code = Node::block("codeBlock", vec![]);
+            if !code_lines.is_empty() {
+                code.content
+                    .push(Node::text(&code_lines.join("\n"), vec![]));
+            }
+            if !language.trim().is_empty() {
+                code = code.with_attr("language", language.trim());
+            }
+
+            nodes.push(code);
+        } else if let Some((level, text)) = heading(trimmed) {
+            nodes.push(Node::block("heading", parse_inline(text)).with_attr("level", level));
+            index += 1;
+        } else if trimmed == "---" || trimmed == "***" {
+            nodes.push(Node::block("rule", vec![]));
+            index += 1;
+        } else if trimmed.starts_with('>') {
+            let quote = lines[index..]
+                .iter()
+                .map(|line| line.trim_start())
+                .take_while(|line| line.starts_with('>'))
+                .map(|line| {
+                    let line = &line[1..];
+                    line.strip_prefix(' ').unwrap_or(line)
+                })
+                .collect::<Vec<_>>();
+            index += quote.len();
+
+            nodes.push(Node::block("blockquote", parse_blocks(&quote)));
+        } else if let Some((ordered, start)) = list_marker(line).map(|marker| marker.kind()) {
+            let (list, consumed) = parse_list(&lines[index..], ordered);
+            index += consumed;
+
+            let mut list = Node::block(if ordered { "orderedList" } else { "bulletList" }, list);
+            if ordered && start != 1 {
+                list = list.with_attr("order", start);
+            }
+
+            nodes.push(list);
+        } else {
+            let paragraph = lines[index..]
+                .iter()
+                .enumerate()
+                .take_while(|(offset, line)| *offset == 0 || !starts_block(line))
+                .map(|(_, line)| line.trim())
+                .collect::<Vec<_>>();
+            index += paragraph.len();
+
+            let mut content = vec![];
+            for (line_index, line) in paragraph.into_iter().enumerate() {
+                if line_index > 0 {
+                    content.push(Node::hard_break());
+                }
+                content.extend(parse_inline(line));
+            }
+
+            nodes.push(Node::block("paragraph", content));
+        }
+    }
+
+    nodes
+}
+
+fn starts_block(line: &str) -> bool {
+    let trimmed = line.trim();
+
+    trimmed.is_empty()
+        || trimmed.starts_with("```")
+        || trimmed.starts_with('>')
+        || heading(trimmed).is_some()
+        || list_marker(line).is_some()
+}
+
+fn heading(line: &str) -> Option<(u64, &str)> {
+    let level = line.chars().take_while(|char| *char == '#').count();
+
+    if (1..=6).contains(&level) {
+        line[level..]
+            .strip_prefix(' ')
+            .map(|text| (level as u64, text.trim()))
+    } else {
+        None
+    }
+}
+
+struct ListMarker {
+    indent: usize,
+    width: usize,
+    number: Option<u64>,
+}
+
+impl ListMarker {
+    fn kind(&self) -> (bool, u64) {
+        (self.number.is_some(), self.number.unwrap_or(1))
+    }
+}
+
+// Only ASCII spaces count, so the indent is always a char boundary.
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn list_marker(line: &str) -> Option<ListMarker> {
+    let indent = indent_of(line);
+    let rest = &line[indent..];
+
+    if rest.starts_with("- ") || rest.starts_with("* ") || rest.starts_with("+ ") {
+        return Some(ListMarker {
+            indent,
+            width: 2,
+            number: None,
+        });
+    }
+
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && rest[digits..].starts_with(". ") {
+        return Some(ListMarker {
+            indent,
+            width: digits + 2,
+            number: rest[..digits].parse().ok(),
+        });
+    }
+
+    None
+}
+
+fn parse_list(lines: &[&str], ordered: bool) -> (Vec<Node>, usize) {
+    let indent = list_marker(lines[0]).map_or(0, |marker| marker.indent);
+
+    let mut items = vec![];
+    let mut index = 0;
+
+    while index < lines.len() {
+        let Some(marker) = list_marker(lines[index]) else {
+            break;
+        };
+
+        if marker.indent != indent || marker.number.is_some() != ordered {
+            break;
+        }
+
+        let content_indent = marker.indent + marker.width;
+        let mut item_lines = vec![&lines[index][content_indent..]];
+        index += 1;
+
+        while index < lines.len() {
+            let line = lines[index];
+            if line.trim().is_empty() || indent_of(line) < content_indent {
+                break;
+            }
+
+            item_lines.push(&line[content_indent..]);
+            index += 1;
+        }
+
+        items.push(Node::block("listItem", parse_blocks(&item_lines)));
+    }
+
+    (items, index)
+}
+
+fn parse_inline(text: &str) -> Vec<Node> {
+    let mut nodes: Vec<Node> = vec![];
+    let mut plain = String::new();
+    let mut rest = text;
+
+    let flush = |plain: &mut String, nodes: &mut Vec<Node>| {
+        if !plain.is_empty() {
+            nodes.push(Node::text(plain, vec![]));
+            plain.clear();
+        }
+    };
+
+    while let Some(char) = rest.chars().next() {
+        let span = if let Some(inner) = rest.strip_prefix('`') {
+            inner
+                .find('`')
+                .map(|end| (vec![Node::text(&inner[..end], vec![mark("code")])], end + 2))
+        } else if let Some(inner) = rest.strip_prefix("**") {
+            enclosed(inner, "**").map(|(nodes, len)| (with_mark(nodes, mark("strong")), len + 2))
+        } else if let Some(inner) = rest.strip_prefix("~~") {
+            enclosed(inner, "~~").map(|(nodes, len)| (with_mark(nodes, mark("strike")), len + 2))
+        } else if let Some(inner) = rest.strip_prefix('*') {
+            enclosed(inner, "*").map(|(nodes, len)| (with_mark(nodes, mark("em")), len + 1))
+        } else if let Some(inner) = rest.strip_prefix('[') {
+            link(inner).map(|(nodes, len)| (nodes, len + 1))
+        } else {
+            None
+        };
+
+        match span {
+            Some((span, len)) => {
+                flush(&mut plain, &mut nodes);
+                nodes.extend(span);
+                rest = &rest[len..];
+            }
+            None => {
+                plain.push(char);
+                rest = &rest[char.len_utf8()..];
+            }
+        }
+    }
+
+    flush(&mut plain, &mut nodes);
+    nodes
+}
+
+// Parses up to the closing `delimiter`, returns the nodes and the consumed length.
+fn enclosed(text: &str, delimiter: &str) -> Option<(Vec<Node>, usize)> {
+    let end = text.find(delimiter).filter(|end| *end > 0)?;
+    Some((parse_inline(&text[..end]), end + delimiter.len()))
+}
+
+fn link(text: &str) -> Option<(Vec<Node>, usize)> {
+    let label_end = text.find("](")?;
+    let href_len = text[label_end + 2..].find(')')?;
+    let href = &text[label_end + 2..label_end + 2 + href_len];
+
+    let mut link = mark("link");
+    link.attrs.insert("href".to_owned(), Value::from(href));
+
+    Some((
+        with_mark(parse_inline(&text[..label_end]), link),
+        label_end + 2 + href_len + 1,
+    ))
+}
+
+fn mark(r#type: &str) -> Mark {
+    Mark {
+        r#type: r#type.to_owned(),
+        attrs: Map::new(),
+    }
+}
+
+fn with_mark(mut nodes: Vec<Node>, mark: Mark) -> Vec<Node> {
+    for node in &mut nodes {
+        node.marks.push(mark.clone());
+    }
+    nodes
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Text {
+    Plain(String),
+    Document(Node),
+}
+
+impl From<Text> for String {
+    fn from(text: Text) -> String {
+        match text {
+            Text::Plain(text) => text,
+            Text::Document(document) => document.to_markdown(),
+        }
+    }
+}
+
+/// Accepts either a plain string (API v2) or an ADF document (API v3), the
+/// latter is converted to markdown.
+pub(crate) fn deserialize_text<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Text::deserialize(deserializer).map(String::from)
+}
+
+pub(crate) fn deserialize_optional_text<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Text>::deserialize(deserializer).map(|text| text.map(String::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn document_to_markdown() {
+        let json = json!({
+            "type": "doc",
+            "version": 1,
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": {"level": 2},
+                    "content": [{"type": "text", "text": "Steps"}]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {"type": "text", "text": "Open "},
+                        {"type": "text", "text": "settings", "marks": [{"type": "strong"}]},
+                        {"type": "text", "text": " and ask "},
+                        {"type": "mention", "attrs": {"id": "5b10ac8d82e05b22cc7d4ef5", "text": "@Vladimir"}},
+                        {"type": "hardBreak"},
+                        {"type": "text", "text": "docs", "marks": [{"type": "link", "attrs": {"href": "https://example.io"}}]}
+                    ]
+                },
+                {
+                    "type": "orderedList",
+                    "content": [
+                        {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "one"}]}]},
+                        {"type": "listItem", "content": [
+                            {"type": "paragraph", "content": [{"type": "text", "text": "two"}]},
+                            {"type": "bulletList", "content": [
+                                {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "nested", "marks": [{"type": "code"}]}]}]}
+                            ]}
+                        ]}
+                    ]
+                },
+                {
+                    "type": "codeBlock",
+                    "attrs": {"language": "rust"},
+                    "content": [{"type": "text", "text": "fn main() {}"}]
+                }
+            ]
+        });
+
+        let document: Node = serde_json::from_value(json).unwrap();
+
+        assert_eq!(
+            document.to_markdown(),
+            "## Steps\n\nOpen **settings** and ask @Vladimir\n[docs](https://example.io)\n\n1. one\n2. two\n   - `nested`\n\n```rust\nfn main() {}\n```"
+        );
+        assert_eq!(
+            document.to_plain_text(),
+            "Steps\n\nOpen settings and ask @Vladimir\ndocs\n\n1. one\n2. two\n   - nested\n\nfn main() {}"
+        );
+    }
+
+    #[test]
+    fn markdown_round_trip() {
+        let markdown = "# Title\n\nSome *emphasis*, **bold**, ~~gone~~ and `code`\nsecond line with [a link](https://example.io)\n\n- first\n- second\n  1. inner\n  2. list\n\n> quoted\n\n---\n\n```\nlet x = 1;\n```";
+
+        let document = Node::from_markdown(markdown);
+
+        assert_eq!(document.r#type, "doc");
+        assert_eq!(document.version, Some(1));
+        assert_eq!(document.to_markdown(), markdown);
+    }
+
+    #[test]
+    fn unicode_list_items() {
+        let document = Node::from_markdown("- a\n \u{a0}b\n- ünïcödé\n  \u{3000}続き\n1. ∑");
+
+        assert_eq!(
+            document.to_markdown(),
+            "- a\n\nb\n\n- ünïcödé\n  続き\n\n1. ∑"
+        );
+    }
+
+    #[test]
+    fn plain_text_document() {
+        let document = Node::from_plain_text("first *line*\nsecond\n\nnext");
+
+        assert_eq!(
+            serde_json::to_value(&document).unwrap(),
+            json!({
+                "type": "doc",
+                "version": 1,
+                "content": [
+                    {"type": "paragraph", "content": [
+                        {"type": "text", "text": "first *line*"},
+                        {"type": "hardBreak"},
+                        {"type": "text", "text": "second"}
+                    ]},
+                    {"type": "paragraph", "content": [{"type": "text", "text": "next"}]}
+                ]
+            })
+        );
+        assert_eq!(
+            TextFormat::PlainText.to_document("*foo*\n1. bar"),
+            Node::from_plain_text("*foo*\n1. bar")
+        );
+    }
+
+    #[test]
+    fn text_deserialization() {
+        #[derive(Deserialize)]
+        struct Body {
+            #[serde(deserialize_with = "deserialize_text")]
+            body: String,
+        }
+
+        let body: Body = serde_json::from_value(json!({"body": "plain"})).unwrap();
+        assert_eq!(body.body, "plain");
+
+        let body: Body = serde_json::from_value(json!({"body": {
+            "type": "doc",
+            "version": 1,
+            "content": [{"type": "paragraph", "content": [{"type": "text", "text": "rich", "marks": [{"type": "em"}]}]}]
+        }}))
+        .unwrap();
+        assert_eq!(body.body, "*rich*");
+    }
+}
@@ -0,0 +1,353 @@
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+
+use chipp_http::curl::easy::{Easy, List};
+use chipp_http::{Interceptor, Request, Response};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+use url::Url;
+
+use crate::builder::Transport;
+use crate::client::clone_request;
+use crate::error::JiraError;
+use crate::user::User;
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    #[serde(deserialize_with = "deserialize_id")]
+    pub id: String,
+    pub filename: String,
+    pub author: Option<User>,
+
+    #[serde(deserialize_with = "crate::date_format::deserialize_date_with_tz")]
+    pub created: DateTime<Utc>,
+
+    /// In bytes.
+    pub size: u64,
+    pub mime_type: Option<String>,
+    /// URL of the file, see `Client::download_attachment`.
+    pub content: String,
+    pub thumbnail: Option<String>,
+}
+
+// Issue fields list ids as strings, `/attachment/{id}` as numbers.
+fn deserialize_id<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Id {
+        String(String),
+        Number(u64),
+    }
+
+    Ok(match Id::deserialize(deserializer)? {
+        Id::String(id) => id,
+        Id::Number(id) => id.to_string(),
+    })
+}
+
+/// A `multipart/form-data` body with a single `file` part, returns the
+/// content type and the body.
+pub(crate) fn multipart(filename: &str, content: &[u8]) -> (String, Vec<u8>) {
+    let boundary = loop {
+        let boundary = format!("jira-api-{:016x}{:016x}", random(), random());
+
+        if !contains(content, boundary.as_bytes()) {
+            break boundary;
+        }
+    };
+
+    // Quotes and line breaks would end the header value.
+    let filename = filename
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+
+    let mut body = Vec::with_capacity(content.len() + 256);
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    (format!("multipart/form-data; boundary={}", boundary), body)
+}
+
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+// Jira Cloud redirects once to the media service.
+pub(crate) const MAX_REDIRECTS: usize = 5;
+
+/// Sends `request` and writes a successful response body to `writer` as it
+/// arrives, chipp_http only hands out complete bodies. Returns the location
+/// of a redirect instead, curl doesn't follow them so every hop can be
+/// authorized on its own.
+pub(crate) async fn fetch<W>(
+    request: Request,
+    transport: &Transport,
+    writer: Arc<Mutex<W>>,
+) -> Result<Option<Url>, JiraError>
+where
+    W: Write + Send + 'static,
+{
+    let transport = transport.clone();
+    let (tx, rx) = futures_channel::oneshot::channel();
+
+    thread::spawn(move || {
+        let result = {
+            let mut writer = writer.lock().unwrap_or_else(PoisonError::into_inner);
+            hop(request, &transport, &mut *writer)
+        };
+
+        // The caller takes the writer back once the result arrives.
+        drop(writer);
+        let _ = tx.send(result);
+    });
+
+    // The sender is only dropped unsent if the thread panicked.
+    match rx.await {
+        Ok(result) => result,
+        Err(_) => Err(io::Error::other("download thread panicked").into()),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn hop<W: Write>(
+    request: Request,
+    transport: &Transport,
+    writer: &mut W,
+) -> Result<Option<Url>, JiraError> {
+    let mut easy = Easy::new();
+
+    if let Err(error) = configure(&mut easy, &request) {
+        return Err(chipp_http::Error::from((request, error)).into());
+    }
+
+    transport.modify(&mut easy, &request);
+
+    let Some(location) = transfer(easy, &request, writer)? else {
+        writer.flush().map_err(JiraError::Io)?;
+        return Ok(None);
+    };
+
+    let url = request.url.join(&location).map_err(|error| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{location}: {error}"))
+    })?;
+
+    Ok(Some(url))
+}
+
+fn configure(easy: &mut Easy, request: &Request) -> Result<(), chipp_http::curl::Error> {
+    easy.url(request.url.as_str())?;
+
+    let mut headers = List::new();
+    for (name, value) in request.headers.iter().flatten() {
+        headers.append(&format!("{}: {}", name, value))?;
+    }
+
+    easy.http_headers(headers)
+}
+
+/// Writes a successful body to `writer`, returns the location of a redirect.
+#[allow(clippy::result_large_err)]
+fn transfer<W: Write>(
+    mut easy: Easy,
+    request: &Request,
+    writer: &mut W,
+) -> Result<Option<String>, JiraError> {
+    // Headers of the last response, interim ones like 100 start over.
+    let headers = RefCell::new(Vec::<String>::new());
+    let mut error_body = vec![];
+    let mut write_error = None;
+    let mut written = false;
+
+    let result = {
+        let mut transfer = easy.transfer();
+
+        let header = transfer.header_function(|header| {
+            let header = String::from_utf8_lossy(header).trim_end().to_owned();
+            let mut headers = headers.borrow_mut();
+
+            if status_code(&header).is_some() {
+                headers.clear();
+            }
+            headers.push(header);
+            true
+        });
+
+        let write = transfer.write_function(|data| {
+            let success = headers
+                .borrow()
+                .first()
+                .and_then(|status| status_code(status))
+                .is_some_and(|status| (200..300).contains(&status));
+
+            if !success {
+                error_body.extend_from_slice(data);
+                return Ok(data.len());
+            }
+
+            written = true;
+
+            match writer.write_all(data) {
+                Ok(()) => Ok(data.len()),
+                Err(error) => {
+                    write_error = Some(error);
+                    // Anything but the full length aborts the transfer.
+                    Ok(0)
+                }
+            }
+        });
+
+        header.and(write).and_then(|_| transfer.perform())
+    };
+
+    if let Some(error) = write_error {
+        return Err(JiraError::Io(error));
+    }
+
+    if let Err(error) = result {
+        // Retrying would write the beginning of the file again.
+        if written {
+            return Err(io::Error::other(format!("download interrupted: {error}")).into());
+        }
+
+        return Err(chipp_http::Error::from((clone_request(request), error)).into());
+    }
+
+    let headers = headers.into_inner();
+    let status_code = headers
+        .first()
+        .and_then(|status| status_code(status))
+        .unwrap_or(0);
+
+    if (300..400).contains(&status_code) {
+        if let Some(location) = header_value(&headers, "location") {
+            return Ok(Some(location.to_owned()));
+        }
+    }
+
+    if !(200..300).contains(&status_code) {
+        let response = Response {
+            status_code,
+            body: error_body,
+            headers,
+        };
+
+        return Err(chipp_http::Error::from((clone_request(request), response)).into());
+    }
+
+    Ok(None)
+}
+
+fn header_value<'a>(headers: &'a [String], name: &str) -> Option<&'a str> {
+    headers.iter().find_map(|header| {
+        let (key, value) = header.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+// `HTTP/1.1 200 OK` or `HTTP/2 302`.
+fn status_code(line: &str) -> Option<u32> {
+    let mut parts = line.split_whitespace();
+
+    match parts.next() {
+        Some(version) if version.starts_with("HTTP/") => parts.next()?.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn attachment_parsing() {
+        let from_fields: Attachment = serde_json::from_value(json!({
+            "id": "10001",
+            "filename": "report.pdf",
+            "author": {"key": "chipp", "name": "chipp"},
+            "created": "2019-10-14T15:59:50.000-0400",
+            "size": 23123,
+            "mimeType": "application/pdf",
+            "content": "https://jira.example.io/secure/attachment/10001/report.pdf"
+        }))
+        .unwrap();
+
+        assert_eq!(from_fields.id, "10001");
+        assert_eq!(from_fields.mime_type.as_deref(), Some("application/pdf"));
+        assert!(from_fields.thumbnail.is_none());
+
+        let metadata: Attachment = serde_json::from_value(json!({
+            "id": 10001,
+            "filename": "report.pdf",
+            "created": "2019-10-14T15:59:50.000-0400",
+            "size": 23123,
+            "content": "https://jira.example.io/secure/attachment/10001/report.pdf"
+        }))
+        .unwrap();
+
+        assert_eq!(metadata.id, "10001");
+    }
+
+    #[test]
+    fn multipart_body() {
+        let (content_type, body) = multipart("say \"hi\".txt", b"hello");
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            format!(
+                "--{boundary}\r\n\
+                 Content-Disposition: form-data; name=\"file\"; filename=\"say %22hi%22.txt\"\r\n\
+                 Content-Type: application/octet-stream\r\n\r\n\
+                 hello\r\n\
+                 --{boundary}--\r\n"
+            )
+        );
+    }
+
+    #[test]
+    fn location_header() {
+        assert_eq!(
+            header_value(
+                &[
+                    "HTTP/1.1 302 Found".to_owned(),
+                    "Location: https://media.example.io/f".to_owned()
+                ],
+                "location"
+            ),
+            Some("https://media.example.io/f")
+        );
+    }
+
+    #[test]
+    fn status_lines() {
+        assert_eq!(status_code("HTTP/1.1 200 OK"), Some(200));
+        assert_eq!(status_code("HTTP/2 302"), Some(302));
+        assert_eq!(status_code("Content-Type: text/plain"), None);
+    }
+}
@@ -1,7 +1,210 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct Board {
-    pub id: u32,
+    pub id: u64,
     pub name: String,
+    pub r#type: Option<BoardType>,
+    pub location: Option<BoardLocation>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BoardType {
+    Scrum,
+    Kanban,
+    Simple,
+}
+
+impl BoardType {
+    fn as_str(self) -> &'static str {
+        match self {
+            BoardType::Scrum => "scrum",
+            BoardType::Kanban => "kanban",
+            BoardType::Simple => "simple",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardLocation {
+    pub project_id: Option<u64>,
+    pub project_key: Option<String>,
+    pub display_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoardFilter {
+    pub project_key_or_id: Option<String>,
+    pub board_type: Option<BoardType>,
+    pub name: Option<String>,
+}
+
+impl BoardFilter {
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![];
+
+        if let Some(project) = &self.project_key_or_id {
+            params.push(("projectKeyOrId", project.clone()));
+        }
+
+        if let Some(board_type) = self.board_type {
+            params.push(("type", board_type.as_str().to_owned()));
+        }
+
+        if let Some(name) = &self.name {
+            params.push(("name", name.clone()));
+        }
+
+        params
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardConfiguration {
+    pub id: u64,
+    pub name: String,
+    pub filter: BoardFilterRef,
+    pub column_config: ColumnConfig,
+    pub estimation: Option<Estimation>,
+}
+
+impl BoardConfiguration {
+    pub fn column_for_status(&self, status_id: &str) -> Option<&BoardColumn> {
+        self.column_config
+            .columns
+            .iter()
+            .find(|column| column.statuses.iter().any(|status| status.id == status_id))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct BoardFilterRef {
+    pub id: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnConfig {
+    pub columns: Vec<BoardColumn>,
+    pub constraint_type: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct BoardColumn {
+    pub name: String,
+    #[serde(default)]
+    pub statuses: Vec<StatusRef>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct StatusRef {
+    pub id: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Estimation {
+    pub r#type: String,
+    pub field: Option<EstimationField>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimationField {
+    pub field_id: String,
+    pub display_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn board_parsing() {
+        let json = json!({
+            "id": 123,
+            "self": "https://jira.example.io/rest/agile/1.0/board/123",
+            "name": "RS board",
+            "type": "scrum",
+            "location": {
+                "projectId": 10000,
+                "displayName": "Rust (RS)",
+                "projectName": "Rust",
+                "projectKey": "RS",
+                "projectTypeKey": "software"
+            }
+        });
+
+        let board: Board = serde_json::from_value(json).unwrap();
+
+        assert_eq!(board.id, 123);
+        assert_eq!(board.r#type, Some(BoardType::Scrum));
+        assert_eq!(
+            board.location.and_then(|location| location.project_key),
+            Some("RS".to_owned())
+        );
+    }
+
+    #[test]
+    fn configuration_parsing() {
+        let json = json!({
+            "id": 123,
+            "name": "RS board",
+            "type": "scrum",
+            "filter": {"id": "10500", "self": "https://jira.example.io/rest/api/2/filter/10500"},
+            "columnConfig": {
+                "columns": [
+                    {"name": "To Do", "statuses": [{"id": "1", "self": "..."}]},
+                    {"name": "In Progress", "statuses": [{"id": "3"}, {"id": "10001"}]},
+                    {"name": "Done", "statuses": [{"id": "6"}]}
+                ],
+                "constraintType": "issueCount"
+            },
+            "estimation": {
+                "type": "field",
+                "field": {"fieldId": "customfield_10182", "displayName": "Story Points"}
+            },
+            "ranking": {"rankCustomFieldId": 10011}
+        });
+
+        let configuration: BoardConfiguration = serde_json::from_value(json).unwrap();
+
+        assert_eq!(configuration.filter.id, "10500");
+        assert_eq!(configuration.column_config.columns.len(), 3);
+        assert_eq!(
+            configuration
+                .column_for_status("10001")
+                .map(|column| column.name.as_str()),
+            Some("In Progress")
+        );
+        assert_eq!(configuration.column_for_status("42"), None);
+        assert_eq!(
+            configuration
+                .estimation
+                .and_then(|estimation| estimation.field)
+                .map(|field| field.field_id),
+            Some("customfield_10182".to_owned())
+        );
+    }
+
+    #[test]
+    fn filter_params() {
+        let filter = BoardFilter {
+            project_key_or_id: Some("RS".to_owned()),
+            board_type: Some(BoardType::Kanban),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            filter.params(),
+            vec![
+                ("projectKeyOrId", "RS".to_owned()),
+                ("type", "kanban".to_owned())
+            ]
+        );
+    }
 }
@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chipp_http::curl::easy::{Easy, List};
+use chipp_http::{Interceptor, Request};
+use log::warn;
+
+use crate::adf::TextFormat;
+use crate::client::{AuthType, Client, Deployment};
+use crate::credentials::CredentialProvider;
+use crate::issue::{Expand, FieldSet};
+use crate::rate_limit::RateLimit;
+use crate::retry::{RetryEvent, RetryHook, RetryPolicy};
+
+/// Configures a `Client` beyond the base URL and authentication.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use jira_api::client::AuthType;
+/// # use jira_api::credentials::EnvCredentials;
+/// # use jira_api::issue::FieldSet;
+/// # use jira_api::{ClientBuilder, RetryPolicy};
+/// let client = ClientBuilder::new("https://jira.example.io", AuthType::AccessToken, EnvCredentials::new())
+///     .timeout(Duration::from_secs(30))
+///     .retry_policy(RetryPolicy::new(5, 1))
+///     .user_agent("sprint-report/1.0")
+///     .default_fields(FieldSet::LABELS | FieldSet::ASSIGNEE)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct ClientBuilder {
+    pub(crate) base_url: String,
+    pub(crate) auth_type: AuthType,
+    pub(crate) credentials: Box<dyn CredentialProvider>,
+    pub(crate) deployment: Option<Deployment>,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) retry_hook: Option<RetryHook>,
+    pub(crate) rate_limit: Option<RateLimit>,
+    pub(crate) default_fields: FieldSet,
+    pub(crate) default_expand: Expand,
+    pub(crate) text_format: TextFormat,
+    pub(crate) transport: Transport,
+}
+
+impl ClientBuilder {
+    pub fn new<U, C>(base_url: U, auth_type: AuthType, credentials: C) -> ClientBuilder
+    where
+        U: ToString,
+        C: CredentialProvider + 'static,
+    {
+        ClientBuilder {
+            base_url: base_url.to_string(),
+            auth_type,
+            credentials: Box::new(credentials),
+            deployment: None,
+            retry_policy: RetryPolicy::default(),
+            retry_hook: None,
+            rate_limit: None,
+            default_fields: FieldSet::NONE,
+            default_expand: Expand::NONE,
+            text_format: TextFormat::default(),
+            transport: Transport::default(),
+        }
+    }
+
+    /// Overrides the deployment detected from the URL.
+    pub fn deployment(mut self, deployment: Deployment) -> ClientBuilder {
+        self.deployment = Some(deployment);
+        self
+    }
+
+    /// Limits the whole request, including the response transfer.
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.transport.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.transport.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> ClientBuilder {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Called before every retry, in addition to the `warn` log record.
+    pub fn on_retry<F>(mut self, hook: F) -> ClientBuilder
+    where
+        F: Fn(&RetryEvent) + Send + Sync + 'static,
+    {
+        self.retry_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Shares one request budget between all calls made through the client.
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> ClientBuilder {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    pub fn user_agent<A: ToString>(mut self, user_agent: A) -> ClientBuilder {
+        self.transport.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Proxy URL in any form curl accepts, e.g. `http://proxy.example.io:3128`.
+    pub fn proxy<P: ToString>(mut self, proxy: P) -> ClientBuilder {
+        self.transport.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// PEM bundle of certificate authorities used instead of the system ones.
+    pub fn ca_bundle<P: AsRef<Path>>(mut self, path: P) -> ClientBuilder {
+        self.transport.ca_bundle = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Fields requested for every issue in addition to the mandatory ones.
+    pub fn default_fields(mut self, fields: FieldSet) -> ClientBuilder {
+        self.default_fields = fields;
+        self
+    }
+
+    /// Expansions requested for every issue, e.g. `Expand::RENDERED_FIELDS`.
+    pub fn default_expand(mut self, expand: Expand) -> ClientBuilder {
+        self.default_expand = expand;
+        self
+    }
+
+    /// How descriptions, comments and worklog comments are sent to Jira
+    /// Cloud, markdown by default. Server always receives the text as is.
+    pub fn text_format(mut self, text_format: TextFormat) -> ClientBuilder {
+        self.text_format = text_format;
+        self
+    }
+
+    /// Returns `None` if the base URL is invalid or has no domain.
+    pub fn build(self) -> Option<Client> {
+        Client::from_builder(self)
+    }
+}
+
+/// curl settings shared by every request of a client.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Transport {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    ca_bundle: Option<PathBuf>,
+}
+
+impl Interceptor for Transport {
+    fn modify(&self, easy: &mut Easy, _: &Request) {
+        let mut results = vec![];
+
+        if let Some(timeout) = self.timeout {
+            results.push(easy.timeout(timeout));
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            results.push(easy.connect_timeout(timeout));
+        }
+
+        if let Some(user_agent) = &self.user_agent {
+            results.push(easy.useragent(user_agent));
+        }
+
+        if let Some(proxy) = &self.proxy {
+            results.push(easy.proxy(proxy));
+        }
+
+        if let Some(ca_bundle) = &self.ca_bundle {
+            results.push(easy.cainfo(ca_bundle));
+        }
+
+        for error in results.into_iter().filter_map(Result::err) {
+            warn!("can't apply client option: {}", error);
+        }
+    }
+
+    fn add_headers(&self, _: &mut List, _: &Request) {}
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 use crate::User;
@@ -9,7 +10,10 @@ pub struct Changelog {
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct History {
+    pub id: String,
     pub author: User,
+    #[serde(deserialize_with = "crate::date_format::deserialize_date_with_tz")]
+    pub created: DateTime<Utc>,
     pub items: Vec<Item>,
 }
 
@@ -1,99 +1,196 @@
 use std::collections::HashSet;
+use std::future::Future;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::Duration;
 
+use chrono::{DateTime, TimeZone, Utc};
 use url::Url;
 use {
-    super::board::Board,
-    super::issue::{Issue, ShortIssue},
+    super::adf::TextFormat,
+    super::attachment::{self, Attachment},
+    super::board::{Board, BoardConfiguration, BoardFilter},
+    super::comment::{Comment, CommentBody, Comments, Visibility},
+    super::field::{FieldMeta, FieldRegistry},
+    super::graph::IssueGraph,
+    super::issue::{Expand, FieldSet, Issue, IssueLinkType, IssueLinkTypes, KeyRef, ShortIssue},
+    super::jql::{ParsedQueries, ParsedQuery, Validation},
+    super::pagination::{paginate, PageOptions},
     super::project::Project,
-    super::sprint::Sprint,
+    super::sprint::{NewSprint, Sprint, SprintState, SprintUpdate},
     super::tempo_log::TempoLog,
-    super::user::User,
-    super::worklog::Worklogs,
+    super::transition::{Transition, Transitions},
+    super::user::{User, UserId},
+    super::worklog::{
+        format_started, AdjustEstimate, Worklog, WorklogBody, WorklogChangePage, WorklogChanges,
+        WorklogCursor, Worklogs,
+    },
 };
 
-use chipp_http::{
-    curl::easy::{Auth, Easy, List},
-    Error, HttpClient, HttpMethod, Interceptor, Request,
-};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chipp_http::{HttpClient, HttpMethod, Request, Response};
+use futures_util::{future, Stream};
 use log::trace;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::issue::{ModifyFields, MANDATORY_ISSUE_FIELDS};
+use crate::builder::{ClientBuilder, Transport};
+use crate::credentials::{CredentialError, CredentialProvider};
+use crate::error::JiraError;
+use crate::issue::{BulkCreateResult, CreateFields, ModifyFields, MANDATORY_ISSUE_FIELDS};
+use crate::oauth::OAuthConfig;
+use crate::rate_limit::RateLimiter;
+use crate::retry::{retry, MethodKind, RetryHook, RetryPolicy};
 
 pub struct Client {
-    inner: HttpClient<Authenticator>,
+    inner: HttpClient<Transport>,
+    transport: Transport,
+    deployment: Deployment,
+    retry_policy: RetryPolicy,
+    retry_hook: Option<RetryHook>,
+    rate_limiter: Option<RateLimiter>,
+    default_fields: FieldSet,
+    default_expand: Expand,
+    text_format: TextFormat,
+    authenticator: Authenticator,
+    fields: RwLock<Arc<FieldRegistry>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deployment {
+    /// Jira Server and Data Center, REST API v2 with plain text bodies.
+    Server,
+    /// Jira Cloud, REST API v3 with Atlassian Document Format bodies.
+    Cloud,
+}
+
+impl Deployment {
+    fn detect(url: &Url) -> Deployment {
+        match url.domain() {
+            Some(domain) if domain.ends_with(".atlassian.net") => Deployment::Cloud,
+            _ => Deployment::Server,
+        }
+    }
+
+    fn api_version(self) -> &'static str {
+        match self {
+            Deployment::Server => "2",
+            Deployment::Cloud => "3",
+        }
+    }
 }
 
 pub enum AuthType {
     BasicAuth,
     AccessToken,
+    /// Cookie based session created with the user and password via
+    /// `/rest/auth/1/session`, renewed when Jira responds with 401.
+    Session,
+    /// OAuth 1.0a signed requests, the access token is used as `oauth_token`.
+    OAuth(OAuthConfig),
 }
 
 struct Authenticator {
     domain: String,
     auth_type: AuthType,
+    credentials: Box<dyn CredentialProvider>,
+    session: Mutex<Option<String>>,
 }
 
-impl Interceptor for Authenticator {
-    fn modify(&self, easy: &mut Easy, _: &Request) {
-        if let AuthType::BasicAuth = self.auth_type {
-            let (user, pass) = jira_user_pass(&self.domain);
-            easy.username(&user).unwrap();
-            easy.password(&pass).unwrap();
-
-            let mut auth = Auth::new();
-            auth.basic(true);
-            easy.http_auth(&auth).unwrap();
+impl Authenticator {
+    fn authorize(&self, request: &mut Request) -> Result<(), CredentialError> {
+        match &self.auth_type {
+            AuthType::BasicAuth => {
+                let (user, pass) = self.credentials.user_and_password(&self.domain)?;
+                let encoded = BASE64.encode(format!("{user}:{pass}"));
+                request.add_header("Authorization", format!("Basic {encoded}"));
+            }
+            AuthType::AccessToken => {
+                let token = self.credentials.access_token(&self.domain)?;
+                request.add_header("Authorization", format!("Bearer {token}"));
+            }
+            AuthType::Session => {
+                if let Some(cookie) = self.session() {
+                    request.add_header("Cookie", cookie);
+                }
+            }
+            AuthType::OAuth(config) => {
+                let token = self.credentials.access_token(&self.domain)?;
+                let header = config.authorization_header(&request.method, &request.url, &token);
+                request.add_header("Authorization", header);
+            }
         }
+
+        Ok(())
     }
 
-    fn add_headers(&self, headers: &mut List, _: &Request) {
-        if let AuthType::AccessToken = self.auth_type {
-            let token = jira_access_token(&self.domain);
-            let header = format!("Authorization: Bearer {token}");
-            headers.append(&header).unwrap();
-        }
+    fn session(&self) -> Option<String> {
+        self.session.lock().unwrap().clone()
+    }
+
+    fn set_session(&self, cookie: Option<String>) {
+        *self.session.lock().unwrap() = cookie;
     }
 }
 
-impl<'a> Client {
-    pub fn new<U>(jira_base_url: U, auth_type: AuthType) -> Option<Client>
+impl Client {
+    pub fn new<U, C>(jira_base_url: U, auth_type: AuthType, credentials: C) -> Option<Client>
     where
         U: AsRef<str>,
+        C: CredentialProvider + 'static,
+    {
+        Client::builder(jira_base_url.as_ref(), auth_type, credentials).build()
+    }
+
+    pub fn builder<U, C>(jira_base_url: U, auth_type: AuthType, credentials: C) -> ClientBuilder
+    where
+        U: ToString,
+        C: CredentialProvider + 'static,
     {
-        let mut jira_base_url = Url::parse(jira_base_url.as_ref()).ok()?;
+        ClientBuilder::new(jira_base_url, auth_type, credentials)
+    }
+
+    pub(crate) fn from_builder(builder: ClientBuilder) -> Option<Client> {
+        let mut jira_base_url = Url::parse(&builder.base_url).ok()?;
         jira_base_url.path_segments_mut().unwrap().push("rest");
 
         let domain = jira_base_url.domain().map(ToOwned::to_owned)?;
+        let deployment = builder
+            .deployment
+            .unwrap_or_else(|| Deployment::detect(&jira_base_url));
         let inner = HttpClient::new(&jira_base_url)
             .unwrap()
-            .with_interceptor(Authenticator { domain, auth_type });
+            .with_interceptor(builder.transport.clone());
 
-        Some(Client { inner })
+        Some(Client {
+            inner,
+            transport: builder.transport,
+            deployment,
+            retry_policy: builder.retry_policy,
+            retry_hook: builder.retry_hook,
+            rate_limiter: builder.rate_limit.map(RateLimiter::new),
+            default_fields: builder.default_fields,
+            default_expand: builder.default_expand,
+            text_format: builder.text_format,
+            authenticator: Authenticator {
+                domain,
+                auth_type: builder.auth_type,
+                credentials: builder.credentials,
+                session: Mutex::new(None),
+            },
+            fields: RwLock::new(Arc::new(FieldRegistry::default())),
+        })
     }
-}
-
-#[cfg(target_os = "macos")]
-fn jira_user_pass(domain: &str) -> (String, String) {
-    chipp_auth::user_and_password(domain)
-}
-
-#[cfg(target_os = "linux")]
-fn jira_user_pass(_: &str) -> String {
-    (
-        std::env::var("JIRA_USER").expect("should have JIRA_USER"),
-        std::env::var("JIRA_PASS").expect("should have JIRA_PASS"),
-    )
-}
 
-#[cfg(target_os = "macos")]
-fn jira_access_token(domain: &str) -> String {
-    chipp_auth::token(domain, "access_token")
-}
+    /// Overrides the deployment detected from the URL, Jira Cloud is assumed
+    /// for `*.atlassian.net` hosts.
+    pub fn with_deployment(mut self, deployment: Deployment) -> Client {
+        self.deployment = deployment;
+        self
+    }
 
-#[cfg(target_os = "linux")]
-fn jira_access_token(_: &str) -> String {
-    std::env::var("JIRA_ACCESS_TOKEN").expect("should have JIRA_ACCESS_TOKEN")
+    pub fn deployment(&self) -> Deployment {
+        self.deployment
+    }
 }
 
 #[derive(Deserialize)]
@@ -115,69 +212,523 @@ pub struct IssuesPageResponse {
 }
 
 impl Client {
-    pub async fn myself(&self) -> Result<User, Error> {
-        self.inner.get(["api", "2", "myself"]).await
+    async fn perform_request<R, P>(&self, request: Request, parse: P) -> Result<R, JiraError>
+    where
+        R: Send + 'static,
+        P: Fn(Request, Response) -> Result<R, chipp_http::Error> + Clone + Send + 'static,
+    {
+        let kind = MethodKind::of(&request.method);
+        self.perform_request_as(kind, request, parse).await
+    }
+
+    // Allows retrying POST requests that don't modify anything, e.g. search.
+    async fn perform_request_as<R, P>(
+        &self,
+        kind: MethodKind,
+        request: Request,
+        parse: P,
+    ) -> Result<R, JiraError>
+    where
+        R: Send + 'static,
+        P: Fn(Request, Response) -> Result<R, chipp_http::Error> + Clone + Send + 'static,
+    {
+        retry(
+            &self.retry_policy,
+            kind,
+            &request.url,
+            self.retry_hook.as_ref(),
+            || self.perform_authorized(clone_request(&request), parse.clone()),
+        )
+        .await
     }
 
-    pub async fn get_project(&self, key: &str) -> Result<Project, Error> {
-        let mut request = self.inner.new_request(&["api", "2", "project", key]);
-        request.set_retry_count(3);
+    async fn perform_authorized<R, P>(&self, request: Request, parse: P) -> Result<R, JiraError>
+    where
+        R: Send + 'static,
+        P: Fn(Request, Response) -> Result<R, chipp_http::Error> + Clone + Send + 'static,
+    {
+        self.perform_authorized_with(request, |request| self.send_request(request, parse.clone()))
+            .await
+    }
 
-        self.inner
+    // Sends `request` through `send` again once the credentials are renewed.
+    async fn perform_authorized_with<R, F, Fut>(
+        &self,
+        request: Request,
+        send: F,
+    ) -> Result<R, JiraError>
+    where
+        F: Fn(Request) -> Fut,
+        Fut: Future<Output = Result<R, JiraError>>,
+    {
+        let retry = clone_request(&request);
+
+        match send(request).await {
+            Err(JiraError::Unauthorized(error)) => {
+                // Cached credentials may be stale, e.g. after a token rotation.
+                let refreshed = self.authenticator.credentials.refresh();
+
+                if matches!(self.authenticator.auth_type, AuthType::Session) {
+                    trace!("session expired, logging in again");
+                    self.authenticator.set_session(None);
+                } else if refreshed {
+                    trace!("credentials refreshed, retrying");
+                } else {
+                    return Err(JiraError::Unauthorized(error));
+                }
+
+                send(retry).await
+            }
+            result => result,
+        }
+    }
+
+    async fn send_request<R, P>(&self, request: Request, parse: P) -> Result<R, JiraError>
+    where
+        R: Send + 'static,
+        P: Fn(Request, Response) -> Result<R, chipp_http::Error> + Send + 'static,
+    {
+        self.send_with(request, |request| async {
+            self.inner
+                .perform_request(request, parse)
+                .await
+                .map_err(JiraError::from)
+        })
+        .await
+    }
+
+    // Authorizes `request` and sends it within the rate limit.
+    async fn send_with<R, F, Fut>(&self, mut request: Request, send: F) -> Result<R, JiraError>
+    where
+        F: FnOnce(Request) -> Fut,
+        Fut: Future<Output = Result<R, JiraError>>,
+    {
+        self.authorize(&mut request).await?;
+
+        let _permit = match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter.acquire().await,
+            None => None,
+        };
+
+        let result = send(request).await;
+
+        if let (Some(rate_limiter), Some(delay)) = (&self.rate_limiter, result_retry_after(&result))
+        {
+            // Longer delays fail the request, other calls shouldn't stall either.
+            rate_limiter.pause(delay.min(self.retry_policy.max_delay));
+        }
+
+        result
+    }
+
+    async fn authorize(&self, request: &mut Request) -> Result<(), JiraError> {
+        if matches!(self.authenticator.auth_type, AuthType::Session)
+            && self.authenticator.session().is_none()
+        {
+            self.login().await?;
+        }
+
+        self.authenticator.authorize(request)?;
+        Ok(())
+    }
+
+    async fn login(&self) -> Result<(), JiraError> {
+        #[derive(Serialize)]
+        struct LoginRequest {
+            username: String,
+            password: String,
+        }
+
+        #[derive(Deserialize)]
+        struct LoginResponse {
+            session: SessionInfo,
+        }
+
+        #[derive(Deserialize)]
+        struct SessionInfo {
+            name: String,
+            value: String,
+        }
+
+        let (username, password) = self
+            .authenticator
+            .credentials
+            .user_and_password(&self.authenticator.domain)?;
+
+        let mut request = self.inner.new_request(&["auth", "1", "session"]);
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(&LoginRequest { username, password });
+
+        let response: LoginResponse = self
+            .inner
             .perform_request(request, chipp_http::json::parse_json)
+            .await?;
+
+        self.authenticator.set_session(Some(format!(
+            "{}={}",
+            response.session.name, response.session.value
+        )));
+
+        Ok(())
+    }
+
+    /// Ends the current session when using `AuthType::Session`.
+    pub async fn logout(&self) -> Result<(), JiraError> {
+        if self.authenticator.session().is_none() {
+            return Ok(());
+        }
+
+        let mut request = self.inner.new_request(&["auth", "1", "session"]);
+        request.set_method(HttpMethod::Delete);
+        self.authenticator.authorize(&mut request)?;
+        self.authenticator.set_session(None);
+
+        self.inner
+            .perform_request(request, chipp_http::parse_void)
+            .await
+            .map_err(JiraError::from)
+    }
+
+    pub fn field_registry(&self) -> Arc<FieldRegistry> {
+        self.fields.read().unwrap().clone()
+    }
+
+    pub fn set_field_registry(&self, registry: FieldRegistry) {
+        *self.fields.write().unwrap() = Arc::new(registry);
+    }
+
+    pub async fn get_fields(&self) -> Result<Vec<FieldMeta>, JiraError> {
+        let request = self.inner.new_request(self.api_path(&["field"]));
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    /// Discovers custom field ids of this Jira instance and uses them for
+    /// all subsequent issue reads and writes.
+    pub async fn load_field_registry(&self) -> Result<Arc<FieldRegistry>, JiraError> {
+        let fields = self.get_fields().await?;
+        self.set_field_registry(FieldRegistry::from_fields(fields));
+
+        Ok(self.field_registry())
+    }
+
+    // Mandatory fields first, then the requested and the default ones.
+    fn issue_fields(&self, fields: &FieldSet) -> Vec<String> {
+        let registry = self.field_registry();
+
+        let mut all_fields = MANDATORY_ISSUE_FIELDS
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        for id in fields
+            .ids(&registry)
+            .chain(self.default_fields.ids(&registry))
+        {
+            if !all_fields.iter().any(|field| field == id) {
+                all_fields.push(id.to_owned());
+            }
+        }
+
+        all_fields
+    }
+
+    fn issue_expand(&self, expand: Expand) -> Vec<&'static str> {
+        (self.default_expand | expand).names().collect()
+    }
+
+    fn api_path<'a>(&self, path: &[&'a str]) -> Vec<&'a str> {
+        let mut segments = vec!["api", self.deployment.api_version()];
+        segments.extend_from_slice(path);
+        segments
+    }
+
+    // API v3 expects rich text as an ADF document instead of a string.
+    fn rich_text(&self, text: &str) -> serde_json::Value {
+        match self.deployment {
+            Deployment::Server => serde_json::Value::from(text),
+            Deployment::Cloud => serde_json::to_value(self.text_format.to_document(text)).unwrap(),
+        }
+    }
+
+    fn localized_fields<F: Serialize>(&self, fields: &F) -> serde_json::Value {
+        let mut fields = serde_json::to_value(fields).unwrap();
+
+        if let Some(fields) = fields.as_object_mut() {
+            self.field_registry().localize(fields);
+
+            for field in ["description", "environment"] {
+                if let Some(serde_json::Value::String(text)) = fields.get(field) {
+                    let text = self.rich_text(text);
+                    fields.insert(field.to_owned(), text);
+                }
+            }
+        }
+
+        fields
+    }
+
+    pub async fn myself(&self) -> Result<User, JiraError> {
+        let request = self.inner.new_request(self.api_path(&["myself"]));
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn get_project(&self, key: &str) -> Result<Project, JiraError> {
+        let request = self.inner.new_request(self.api_path(&["project", key]));
+
+        self.perform_request(request, chipp_http::json::parse_json)
             .await
     }
 
     pub async fn get_issue(
         &self,
         key: &str,
-        fields: Option<&[&str]>,
-        expand: Option<&[&str]>,
-    ) -> Result<Issue, Error> {
-        let fields = fields.unwrap_or_default();
-        let mut all_fields = HashSet::<&str>::new();
-        all_fields.extend(fields);
-        all_fields.extend(MANDATORY_ISSUE_FIELDS);
+        fields: &FieldSet,
+        expand: Expand,
+    ) -> Result<Issue, JiraError> {
+        let fields = self.issue_fields(fields).join(",");
 
-        let fields = all_fields.into_iter().collect::<Vec<_>>().join(",");
+        let expand = self.issue_expand(expand).join(",");
 
-        let expand = expand.unwrap_or_default().join(",");
-
-        let mut request = self.inner.new_request_with_params(
-            &["api", "2", "issue", key],
+        let request = self.inner.new_request_with_params(
+            self.api_path(&["issue", key]),
             [("fields", fields), ("expand", expand)],
         );
-        request.set_retry_count(3);
 
-        self.inner
-            .perform_request(request, chipp_http::json::parse_json)
+        self.perform_request(request, issue_parser(self.field_registry()))
             .await
     }
 
-    pub async fn get_board(&self, board_id: u16) -> Result<Board, Error> {
-        let mut request =
-            self.inner
-                .new_request(&["agile", "1.0", "board", &format!("{}", board_id)]);
-        request.set_retry_count(3);
+    /// Crawls links, parents and subtasks starting at `root`, `depth` hops
+    /// away at most. Linked issues that are missing or not visible stay in
+    /// the graph without their fields.
+    pub async fn get_issue_graph(&self, root: &str, depth: u32) -> Result<IssueGraph, JiraError> {
+        let fields = FieldSet::ISSUE_LINKS
+            | FieldSet::PARENT
+            | FieldSet::SUBTASKS
+            | FieldSet::ORIGINAL_ESTIMATE;
 
-        self.inner
-            .perform_request(request, chipp_http::json::parse_json)
+        let mut graph = IssueGraph::new();
+        let mut visited = HashSet::from([root.to_owned()]);
+        let mut level = vec![root.to_owned()];
+
+        for hop in 0..=depth {
+            let issues = future::join_all(
+                level
+                    .iter()
+                    .map(|key| self.get_issue(key, &fields, Expand::NONE)),
+            )
+            .await;
+
+            for issue in issues {
+                match issue {
+                    Ok(issue) => graph.add_issue(issue),
+                    Err(JiraError::NotFound(_) | JiraError::Forbidden(_)) if hop > 0 => {}
+                    Err(error) => return Err(error),
+                }
+            }
+
+            if hop == depth {
+                break;
+            }
+
+            level = level
+                .iter()
+                .flat_map(|key| graph.neighbours(key))
+                .filter(|key| !visited.contains(*key))
+                .map(ToOwned::to_owned)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+
+            visited.extend(level.iter().cloned());
+        }
+
+        Ok(graph)
+    }
+
+    pub async fn get_board(&self, board_id: u64) -> Result<Board, JiraError> {
+        let request = self
+            .inner
+            .new_request(&["agile", "1.0", "board", &format!("{}", board_id)]);
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn list_boards(
+        &self,
+        filter: &BoardFilter,
+        start_at: u32,
+        max_results: u32,
+    ) -> Result<AgilePageResponse<Board>, JiraError> {
+        let mut params = filter.params();
+        params.push(("startAt", format!("{}", start_at)));
+        params.push(("maxResults", format!("{}", max_results)));
+
+        let request = self
+            .inner
+            .new_request_with_params(&["agile", "1.0", "board"], &params);
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub fn list_boards_stream<'a>(
+        &'a self,
+        filter: &'a BoardFilter,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<Board, JiraError>> + 'a {
+        paginate(options, move |start_at, max_results| {
+            self.list_boards(filter, start_at, max_results)
+        })
+    }
+
+    pub async fn get_board_configuration(
+        &self,
+        board_id: u64,
+    ) -> Result<BoardConfiguration, JiraError> {
+        let request = self.inner.new_request(&[
+            "agile",
+            "1.0",
+            "board",
+            &format!("{}", board_id),
+            "configuration",
+        ]);
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn get_backlog_issues(
+        &self,
+        board_id: u64,
+        start_at: u32,
+        max_results: u32,
+        fields: &FieldSet,
+    ) -> Result<IssuesPageResponse, JiraError> {
+        let board_id = format!("{}", board_id);
+        self.get_agile_issues(
+            &["agile", "1.0", "board", &board_id, "backlog"],
+            None,
+            start_at,
+            max_results,
+            fields,
+        )
+        .await
+    }
+
+    pub fn get_backlog_issues_stream<'a>(
+        &'a self,
+        board_id: u64,
+        fields: &'a FieldSet,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<Issue, JiraError>> + 'a {
+        paginate(options, move |start_at, max_results| {
+            self.get_backlog_issues(board_id, start_at, max_results, fields)
+        })
+    }
+
+    pub async fn get_board_issues(
+        &self,
+        board_id: u64,
+        jql: Option<&str>,
+        start_at: u32,
+        max_results: u32,
+        fields: &FieldSet,
+    ) -> Result<IssuesPageResponse, JiraError> {
+        let board_id = format!("{}", board_id);
+        self.get_agile_issues(
+            &["agile", "1.0", "board", &board_id, "issue"],
+            jql,
+            start_at,
+            max_results,
+            fields,
+        )
+        .await
+    }
+
+    pub fn get_board_issues_stream<'a>(
+        &'a self,
+        board_id: u64,
+        jql: Option<&'a str>,
+        fields: &'a FieldSet,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<Issue, JiraError>> + 'a {
+        paginate(options, move |start_at, max_results| {
+            self.get_board_issues(board_id, jql, start_at, max_results, fields)
+        })
+    }
+
+    async fn get_agile_issues(
+        &self,
+        path: &[&str],
+        jql: Option<&str>,
+        start_at: u32,
+        max_results: u32,
+        fields: &FieldSet,
+    ) -> Result<IssuesPageResponse, JiraError> {
+        let mut params = vec![
+            ("startAt", format!("{}", start_at)),
+            ("maxResults", format!("{}", max_results)),
+            ("fields", self.issue_fields(fields).join(",")),
+        ];
+
+        let expand = self.issue_expand(Expand::NONE);
+        if !expand.is_empty() {
+            params.push(("expand", expand.join(",")));
+        }
+
+        if let Some(jql) = jql {
+            params.push(("jql", jql.to_owned()));
+        }
+
+        let request = self.inner.new_request_with_params(path, &params);
+
+        self.perform_request(request, issue_parser(self.field_registry()))
             .await
     }
 
     pub async fn get_sprints_for_board(
         &self,
-        board_id: u16,
+        board_id: u64,
         start_at: u16,
-    ) -> Result<AgilePageResponse<Sprint>, Error> {
-        let mut request = self.inner.new_request_with_params(
+    ) -> Result<AgilePageResponse<Sprint>, JiraError> {
+        self.get_sprints_page(board_id, start_at as u32, None).await
+    }
+
+    pub fn get_sprints_for_board_stream(
+        &self,
+        board_id: u64,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<Sprint, JiraError>> + '_ {
+        paginate(options, move |start_at, max_results| {
+            self.get_sprints_page(board_id, start_at, Some(max_results))
+        })
+    }
+
+    async fn get_sprints_page(
+        &self,
+        board_id: u64,
+        start_at: u32,
+        max_results: Option<u32>,
+    ) -> Result<AgilePageResponse<Sprint>, JiraError> {
+        let mut params = vec![("startAt", format!("{}", start_at))];
+
+        // Jira picks the page size unless a stream asks for one.
+        if let Some(max_results) = max_results {
+            params.push(("maxResults", format!("{}", max_results)));
+        }
+
+        let request = self.inner.new_request_with_params(
             &["agile", "1.0", "board", &format!("{}", board_id), "sprint"],
-            &[("startAt", format!("{}", start_at))],
+            &params,
         );
-        request.set_retry_count(3);
 
-        self.inner
-            .perform_request(request, chipp_http::json::parse_json)
+        self.perform_request(request, chipp_http::json::parse_json)
             .await
     }
 
@@ -186,104 +737,450 @@ impl Client {
         jql: &str,
         start_at: u32,
         max_results: u32,
-        fields: Option<&[&str]>,
-        expand: Option<&[&str]>,
-    ) -> Result<IssuesPageResponse, Error> {
+        fields: &FieldSet,
+        expand: Expand,
+    ) -> Result<IssuesPageResponse, JiraError> {
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
         struct Body<'a> {
             jql: &'a str,
             start_at: u32,
             max_results: u32,
-            fields: Option<&'a [&'a str]>,
+            fields: &'a [String],
             expand: Option<&'a [&'a str]>,
         }
 
-        let fields = fields.unwrap_or_default();
-        let mut all_fields = HashSet::<&str>::new();
-        all_fields.extend(fields);
-        all_fields.extend(MANDATORY_ISSUE_FIELDS);
-
-        let fields = all_fields.into_iter().collect::<Vec<_>>();
+        let fields = self.issue_fields(fields);
+        let expand = self.issue_expand(expand);
 
         let body = Body {
             jql,
             start_at,
             max_results,
-            fields: Some(&fields),
-            expand,
+            fields: &fields,
+            expand: Some(expand.as_slice()).filter(|expand| !expand.is_empty()),
         };
 
-        let mut request = self.inner.new_request(&["api", "2", "search"]);
+        let mut request = self.inner.new_request(self.api_path(&["search"]));
         request.set_method(HttpMethod::Post);
-        request.set_retry_count(3);
         request.set_json_body(&body);
 
-        self.inner
-            .perform_request(request, chipp_http::json::parse_json)
-            .await
+        self.perform_request_as(
+            MethodKind::Idempotent,
+            request,
+            issue_parser(self.field_registry()),
+        )
+        .await
     }
 
-    pub async fn get_user_by_username<U>(&self, username: U) -> Result<User, Error>
-    where
-        U: std::fmt::Display + AsRef<str>,
-    {
-        trace!("loading user information {}", username);
+    pub fn search_issues_stream<'a>(
+        &'a self,
+        jql: &'a str,
+        fields: &'a FieldSet,
+        expand: Expand,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<Issue, JiraError>> + 'a {
+        paginate(options, move |start_at, max_results| {
+            self.search_issues(jql, start_at, max_results, fields, expand)
+        })
+    }
 
-        let result = self
-            .inner
-            .get_with_params(
-                &["api", "2", "user"],
-                &[("username", username.as_ref()), ("expand", "groups")],
-            )
-            .await;
+    /// Checks queries with Jira's parser, errors are returned per query
+    /// rather than failing the request.
+    pub async fn parse_jql(
+        &self,
+        queries: &[&str],
+        validation: Validation,
+    ) -> Result<Vec<ParsedQuery>, JiraError> {
+        #[derive(Serialize)]
+        struct Body<'a> {
+            queries: &'a [&'a str],
+        }
 
-        trace!("loaded user information {}", username);
+        let mut request = self.inner.new_request_with_params(
+            self.api_path(&["jql", "parse"]),
+            &[("validation", validation.as_str())],
+        );
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(&Body { queries });
 
-        result
+        let parsed: ParsedQueries = self
+            .perform_request_as(
+                MethodKind::Idempotent,
+                request,
+                chipp_http::json::parse_json,
+            )
+            .await?;
+
+        Ok(parsed.queries)
     }
 
-    pub async fn get_user_by_key<U>(&self, key: U) -> Result<User, Error>
-    where
-        U: std::fmt::Display + AsRef<str>,
-    {
-        let mut request = self
-            .inner
-            .new_request_with_params(&["api", "2", "user"], &[("key", key)]);
-        request.set_retry_count(3);
+    pub async fn create_sprint(&self, sprint: &NewSprint) -> Result<Sprint, JiraError> {
+        let mut request = self.inner.new_request(&["agile", "1.0", "sprint"]);
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(sprint);
 
-        self.inner
-            .perform_request(request, chipp_http::json::parse_json)
+        self.perform_request(request, chipp_http::json::parse_json)
             .await
     }
 
-    pub async fn get_worklogs_for_issue(
+    pub async fn update_sprint(
         &self,
-        issue_id: &str,
-        start_at: u32,
-    ) -> Result<Worklogs, Error> {
-        let mut request = self.inner.new_request_with_params(
-            &["api", "2", "issue", issue_id, "worklog"],
-            &[
-                ("startAt", format!("{}", start_at).as_str()),
-                ("maxResults", "500"),
-            ],
+        sprint_id: u64,
+        update: &SprintUpdate,
+    ) -> Result<Sprint, JiraError> {
+        let mut request =
+            self.inner
+                .new_request(&["agile", "1.0", "sprint", &format!("{}", sprint_id)]);
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(update);
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn start_sprint(
+        &self,
+        sprint_id: u64,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    ) -> Result<Sprint, JiraError> {
+        let update = SprintUpdate {
+            state: Some(SprintState::Active),
+            start_date: Some(start_date),
+            end_date: Some(end_date),
+            ..Default::default()
+        };
+
+        self.update_sprint(sprint_id, &update).await
+    }
+
+    pub async fn close_sprint(&self, sprint_id: u64) -> Result<Sprint, JiraError> {
+        let update = SprintUpdate {
+            state: Some(SprintState::Closed),
+            ..Default::default()
+        };
+
+        self.update_sprint(sprint_id, &update).await
+    }
+
+    pub async fn delete_sprint(&self, sprint_id: u64) -> Result<(), JiraError> {
+        let mut request =
+            self.inner
+                .new_request(&["agile", "1.0", "sprint", &format!("{}", sprint_id)]);
+        request.set_method(HttpMethod::Delete);
+
+        self.perform_request(request, chipp_http::parse_void).await
+    }
+
+    pub async fn get_sprint_issues(
+        &self,
+        sprint_id: u64,
+        start_at: u32,
+        max_results: u32,
+        fields: &FieldSet,
+    ) -> Result<IssuesPageResponse, JiraError> {
+        let sprint_id = format!("{}", sprint_id);
+        self.get_agile_issues(
+            &["agile", "1.0", "sprint", &sprint_id, "issue"],
+            None,
+            start_at,
+            max_results,
+            fields,
+        )
+        .await
+    }
+
+    pub fn get_sprint_issues_stream<'a>(
+        &'a self,
+        sprint_id: u64,
+        fields: &'a FieldSet,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<Issue, JiraError>> + 'a {
+        paginate(options, move |start_at, max_results| {
+            self.get_sprint_issues(sprint_id, start_at, max_results, fields)
+        })
+    }
+
+    pub async fn move_issues_to_sprint(
+        &self,
+        sprint_id: u64,
+        issues: &[&str],
+    ) -> Result<(), JiraError> {
+        let sprint_id = format!("{}", sprint_id);
+        self.move_issues(&["agile", "1.0", "sprint", &sprint_id, "issue"], issues)
+            .await
+    }
+
+    pub async fn move_issues_to_backlog(&self, issues: &[&str]) -> Result<(), JiraError> {
+        self.move_issues(&["agile", "1.0", "backlog", "issue"], issues)
+            .await
+    }
+
+    async fn move_issues(&self, path: &[&str], issues: &[&str]) -> Result<(), JiraError> {
+        #[derive(Serialize)]
+        struct Body<'a> {
+            issues: &'a [&'a str],
+        }
+
+        // Agile API accepts at most 50 issues per request
+        for issues in issues.chunks(50) {
+            let mut request = self.inner.new_request(path);
+            request.set_method(HttpMethod::Post);
+            request.set_json_body(&Body { issues });
+
+            self.perform_request(request, chipp_http::parse_void)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_user_by_username<U>(&self, username: U) -> Result<User, JiraError>
+    where
+        U: std::fmt::Display + AsRef<str>,
+    {
+        trace!("loading user information {}", username);
+
+        let request = self.inner.new_request_with_params(
+            self.api_path(&["user"]),
+            &[("username", username.as_ref()), ("expand", "groups")],
         );
-        request.set_retry_count(3);
 
-        self.inner
+        let result = self
             .perform_request(request, chipp_http::json::parse_json)
+            .await;
+
+        trace!("loaded user information {}", username);
+
+        result
+    }
+
+    pub async fn get_user(&self, id: &UserId) -> Result<User, JiraError> {
+        let request = self
+            .inner
+            .new_request_with_params(self.api_path(&["user"]), &[id.param()]);
+
+        self.perform_request(request, chipp_http::json::parse_json)
             .await
     }
 
-    pub async fn get_subtasks_for_issue(&self, issue_id: &str) -> Result<Vec<ShortIssue>, Error> {
-        let mut request = self
+    pub async fn get_user_by_key<U>(&self, key: U) -> Result<User, JiraError>
+    where
+        U: std::fmt::Display + AsRef<str>,
+    {
+        let request = self
             .inner
-            .new_request(&["api", "2", "issue", issue_id, "subtask"]);
-        request.set_retry_count(3);
+            .new_request_with_params(self.api_path(&["user"]), &[("key", key)]);
 
-        self.inner
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn get_worklogs_for_issue(
+        &self,
+        issue_id: &str,
+        start_at: u32,
+    ) -> Result<Worklogs, JiraError> {
+        self.get_worklogs_page(issue_id, start_at, 500).await
+    }
+
+    pub fn get_worklogs_for_issue_stream<'a>(
+        &'a self,
+        issue_id: &'a str,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<Worklog, JiraError>> + 'a {
+        paginate(options, move |start_at, max_results| {
+            self.get_worklogs_page(issue_id, start_at, max_results)
+        })
+    }
+
+    async fn get_worklogs_page(
+        &self,
+        issue_id: &str,
+        start_at: u32,
+        max_results: u32,
+    ) -> Result<Worklogs, JiraError> {
+        let request = self.inner.new_request_with_params(
+            self.api_path(&["issue", issue_id, "worklog"]),
+            &[
+                ("startAt", format!("{}", start_at)),
+                ("maxResults", format!("{}", max_results)),
+            ],
+        );
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    /// Logs `time_spent` seconds on the issue and returns the created worklog.
+    pub async fn add_worklog<Tz>(
+        &self,
+        issue_id: &str,
+        started: &DateTime<Tz>,
+        time_spent: u32,
+        comment: Option<&str>,
+        adjust: &AdjustEstimate,
+    ) -> Result<Worklog, JiraError>
+    where
+        Tz: TimeZone,
+        Tz::Offset: std::fmt::Display,
+    {
+        let mut request = self.inner.new_request_with_params(
+            self.api_path(&["issue", issue_id, "worklog"]),
+            adjust.params("reduceBy"),
+        );
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(&WorklogBody {
+            started: format_started(started),
+            time_spent_seconds: time_spent,
+            comment: comment.map(|comment| self.rich_text(comment)),
+        });
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    /// Replaces the start, time spent and comment of a worklog, a `None`
+    /// comment keeps the current one. `AdjustEstimate::Manual` fails with
+    /// `JiraError::Validation` without sending the request.
+    pub async fn update_worklog<Tz>(
+        &self,
+        issue_id: &str,
+        worklog_id: &str,
+        started: &DateTime<Tz>,
+        time_spent: u32,
+        comment: Option<&str>,
+        adjust: &AdjustEstimate,
+    ) -> Result<Worklog, JiraError>
+    where
+        Tz: TimeZone,
+        Tz::Offset: std::fmt::Display,
+    {
+        let mut request = self.inner.new_request_with_params(
+            self.api_path(&["issue", issue_id, "worklog", worklog_id]),
+            adjust.update_params().map_err(JiraError::Validation)?,
+        );
+        request.set_method(HttpMethod::Put);
+        request.set_json_body(&WorklogBody {
+            started: format_started(started),
+            time_spent_seconds: time_spent,
+            comment: comment.map(|comment| self.rich_text(comment)),
+        });
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn delete_worklog(
+        &self,
+        issue_id: &str,
+        worklog_id: &str,
+        adjust: &AdjustEstimate,
+    ) -> Result<(), JiraError> {
+        let mut request = self.inner.new_request_with_params(
+            self.api_path(&["issue", issue_id, "worklog", worklog_id]),
+            adjust.params("increaseBy"),
+        );
+        request.set_method(HttpMethod::Delete);
+
+        self.perform_request(request, chipp_http::parse_void).await
+    }
+
+    /// The next batch of worklogs updated and deleted across all issues,
+    /// about a thousand of each at most. Call again with the returned cursor
+    /// while `has_more` is set.
+    pub async fn sync_worklogs(&self, cursor: WorklogCursor) -> Result<WorklogChanges, JiraError> {
+        let updated = self
+            .get_worklog_changes("updated", cursor.updated())
+            .await?;
+        let deleted = self
+            .get_worklog_changes("deleted", cursor.deleted())
+            .await?;
+
+        let ids = updated
+            .values
+            .iter()
+            .map(|change| change.worklog_id)
+            .collect::<Vec<_>>();
+
+        Ok(WorklogChanges {
+            updated: self.get_worklogs_by_ids(&ids).await?,
+            deleted: deleted
+                .values
+                .iter()
+                .map(|change| change.worklog_id)
+                .collect(),
+            cursor: cursor.advance(&updated, &deleted),
+            has_more: !updated.last_page || !deleted.last_page,
+        })
+    }
+
+    /// `kind` is `updated` or `deleted`.
+    async fn get_worklog_changes(
+        &self,
+        kind: &str,
+        since: i64,
+    ) -> Result<WorklogChangePage, JiraError> {
+        let request = self.inner.new_request_with_params(
+            self.api_path(&["worklog", kind]),
+            &[("since", format!("{}", since))],
+        );
+        let url = request.url.clone();
+
+        let page: WorklogChangePage = self
             .perform_request(request, chipp_http::json::parse_json)
+            .await?;
+
+        if page.is_stalled(since) {
+            let error = <serde_json::Error as serde::de::Error>::custom(format!(
+                "{kind} worklogs stuck at {since}"
+            ));
+            return Err(chipp_http::Error::from((Request::new(url), error)).into());
+        }
+
+        Ok(page)
+    }
+
+    /// Worklogs by id regardless of their issue, ids that don't exist or
+    /// aren't visible are skipped.
+    pub async fn get_worklogs_by_ids(&self, ids: &[u64]) -> Result<Vec<Worklog>, JiraError> {
+        #[derive(Serialize)]
+        struct Body<'a> {
+            ids: &'a [u64],
+        }
+
+        let mut worklogs = Vec::with_capacity(ids.len());
+
+        // Jira accepts at most 1000 ids per request.
+        for ids in ids.chunks(1000) {
+            let mut request = self.inner.new_request(self.api_path(&["worklog", "list"]));
+            request.set_method(HttpMethod::Post);
+            request.set_json_body(&Body { ids });
+
+            let page: Vec<Worklog> = self
+                .perform_request_as(
+                    MethodKind::Idempotent,
+                    request,
+                    chipp_http::json::parse_json,
+                )
+                .await?;
+
+            worklogs.extend(page);
+        }
+
+        Ok(worklogs)
+    }
+
+    pub async fn get_subtasks_for_issue(
+        &self,
+        issue_id: &str,
+    ) -> Result<Vec<ShortIssue>, JiraError> {
+        let request = self
+            .inner
+            .new_request(self.api_path(&["issue", issue_id, "subtask"]));
+
+        self.perform_request(request, chipp_http::json::parse_json)
             .await
     }
 
@@ -292,8 +1189,8 @@ impl Client {
         user: &str,
         date_from: &str,
         date_to: &str,
-    ) -> Result<Vec<TempoLog>, Error> {
-        let mut request = self.inner.new_request_with_params(
+    ) -> Result<Vec<TempoLog>, JiraError> {
+        let request = self.inner.new_request_with_params(
             &["tempo-timesheets", "3", "worklogs"],
             &[
                 ("dateFrom", date_from),
@@ -301,33 +1198,81 @@ impl Client {
                 ("username", user),
             ],
         );
-        request.set_retry_count(3);
 
-        self.inner
-            .perform_request(request, chipp_http::json::parse_json)
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn create_issue(&self, fields: CreateFields) -> Result<ShortIssue, JiraError> {
+        #[derive(Serialize)]
+        struct RequestBody {
+            fields: serde_json::Value,
+        }
+
+        let fields = self.localized_fields(&fields);
+
+        let mut request = self.inner.new_request(self.api_path(&["issue"]));
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(&RequestBody { fields });
+
+        self.perform_request(request, chipp_http::json::parse_json)
             .await
     }
 
-    pub async fn update_issue(&self, key: &str, modify: ModifyFields) -> Result<(), Error> {
+    /// Creates several issues at once. Items rejected by Jira are reported in
+    /// `BulkCreateResult::errors` instead of failing the whole batch.
+    pub async fn create_issues(
+        &self,
+        issues: Vec<CreateFields>,
+    ) -> Result<BulkCreateResult, JiraError> {
+        #[derive(Serialize)]
+        struct IssueUpdate {
+            fields: serde_json::Value,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RequestBody {
+            issue_updates: Vec<IssueUpdate>,
+        }
+
+        let body = RequestBody {
+            issue_updates: issues
+                .into_iter()
+                .map(|fields| IssueUpdate {
+                    fields: self.localized_fields(&fields),
+                })
+                .collect(),
+        };
+
+        let mut request = self.inner.new_request(self.api_path(&["issue", "bulk"]));
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(&body);
+
+        self.perform_request(request, parse_bulk_create_result)
+            .await
+    }
+
+    pub async fn update_issue(&self, key: &str, modify: ModifyFields) -> Result<(), JiraError> {
         #[derive(Debug, Serialize)]
         struct RequestBody {
-            fields: ModifyFields,
+            fields: serde_json::Value,
         }
 
-        let mut request = self.inner.new_request(&["api", "2", "issue", key]);
+        let mut request = self.inner.new_request(self.api_path(&["issue", key]));
         request.method = HttpMethod::Put;
 
-        let body = RequestBody { fields: modify };
+        let body = RequestBody {
+            fields: self.localized_fields(&modify),
+        };
         request.body = Some(serde_json::to_vec(&body).unwrap());
         request.add_header("Content-Type", "application/json; charset=utf-8");
 
-        self.inner
-            .perform_request(request, chipp_http::parse_void)
-            .await
+        self.perform_request(request, chipp_http::parse_void).await
     }
 
-    pub async fn update_issue_labels(&self, key: &str, labels: &[String]) -> Result<(), Error> {
-        let mut request = self.inner.new_request(&["api", "2", "issue", key]);
+    pub async fn update_issue_labels(&self, key: &str, labels: &[String]) -> Result<(), JiraError> {
+        let mut request = self.inner.new_request(self.api_path(&["issue", key]));
         request.method = HttpMethod::Put;
 
         let body = serde_json::json!({
@@ -338,8 +1283,374 @@ impl Client {
         request.body = Some(serde_json::to_vec(&body).unwrap());
         request.add_header("Content-Type", "application/json; charset=utf-8");
 
-        self.inner
-            .perform_request(request, chipp_http::parse_void)
+        self.perform_request(request, chipp_http::parse_void).await
+    }
+
+    pub async fn get_transitions(&self, key: &str) -> Result<Vec<Transition>, JiraError> {
+        let request = self.inner.new_request_with_params(
+            self.api_path(&["issue", key, "transitions"]),
+            &[("expand", "transitions.fields")],
+        );
+
+        let transitions: Transitions = self
+            .perform_request(request, chipp_http::json::parse_json)
+            .await?;
+
+        Ok(transitions.transitions)
+    }
+
+    pub async fn transition_issue(
+        &self,
+        key: &str,
+        transition_id: &str,
+        fields: Option<serde_json::Value>,
+        comment: Option<&str>,
+    ) -> Result<(), JiraError> {
+        let mut body = serde_json::json!({
+            "transition": {
+                "id": transition_id
+            }
+        });
+
+        if let Some(fields) = fields {
+            body["fields"] = fields;
+        }
+
+        if let Some(comment) = comment {
+            body["update"] = serde_json::json!({
+                "comment": [{ "add": { "body": self.rich_text(comment) } }]
+            });
+        }
+
+        let mut request = self
+            .inner
+            .new_request(self.api_path(&["issue", key, "transitions"]));
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(&body);
+
+        self.perform_request(request, chipp_http::parse_void).await
+    }
+
+    /// Moves the issue to the status named `status_name` (case-insensitive).
+    ///
+    /// Returns the executed transition, or `None` if the workflow has no
+    /// transition to that status from the current one.
+    pub async fn transition_issue_to_status(
+        &self,
+        key: &str,
+        status_name: &str,
+        fields: Option<serde_json::Value>,
+        comment: Option<&str>,
+    ) -> Result<Option<Transition>, JiraError> {
+        let transition = self
+            .get_transitions(key)
+            .await?
+            .into_iter()
+            .find(|transition| transition.to.name.eq_ignore_ascii_case(status_name));
+
+        let Some(transition) = transition else {
+            return Ok(None);
+        };
+
+        self.transition_issue(key, &transition.id, fields, comment)
+            .await?;
+
+        Ok(Some(transition))
+    }
+
+    pub async fn get_comments(
+        &self,
+        key: &str,
+        start_at: u32,
+        max_results: u32,
+    ) -> Result<Comments, JiraError> {
+        let request = self.inner.new_request_with_params(
+            self.api_path(&["issue", key, "comment"]),
+            &[
+                ("startAt", format!("{}", start_at)),
+                ("maxResults", format!("{}", max_results)),
+            ],
+        );
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub fn get_comments_stream<'a>(
+        &'a self,
+        key: &'a str,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<Comment, JiraError>> + 'a {
+        paginate(options, move |start_at, max_results| {
+            self.get_comments(key, start_at, max_results)
+        })
+    }
+
+    pub async fn add_comment(
+        &self,
+        key: &str,
+        body: &str,
+        visibility: Option<&Visibility>,
+    ) -> Result<Comment, JiraError> {
+        let mut request = self
+            .inner
+            .new_request(self.api_path(&["issue", key, "comment"]));
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(&CommentBody {
+            body: self.rich_text(body),
+            visibility,
+        });
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn update_comment(
+        &self,
+        key: &str,
+        comment_id: &str,
+        body: &str,
+        visibility: Option<&Visibility>,
+    ) -> Result<Comment, JiraError> {
+        let mut request = self
+            .inner
+            .new_request(self.api_path(&["issue", key, "comment", comment_id]));
+        request.set_method(HttpMethod::Put);
+        request.set_json_body(&CommentBody {
+            body: self.rich_text(body),
+            visibility,
+        });
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn delete_comment(&self, key: &str, comment_id: &str) -> Result<(), JiraError> {
+        let mut request = self
+            .inner
+            .new_request(self.api_path(&["issue", key, "comment", comment_id]));
+        request.set_method(HttpMethod::Delete);
+
+        self.perform_request(request, chipp_http::parse_void).await
+    }
+
+    /// Attaches a file to the issue, `content` is read to the end before the upload.
+    pub async fn add_attachment<R: Read>(
+        &self,
+        key: &str,
+        filename: &str,
+        mut content: R,
+    ) -> Result<Vec<Attachment>, JiraError> {
+        let mut buffer = vec![];
+        content.read_to_end(&mut buffer)?;
+
+        let (content_type, body) = attachment::multipart(filename, &buffer);
+
+        let mut request = self
+            .inner
+            .new_request(self.api_path(&["issue", key, "attachments"]));
+        request.set_method(HttpMethod::Post);
+        request.add_header("Content-Type", content_type);
+        // Jira rejects multipart requests without it as possible XSRF.
+        request.add_header("X-Atlassian-Token", "no-check");
+        request.body = Some(body);
+
+        self.perform_request(request, chipp_http::json::parse_json)
+            .await
+    }
+
+    pub async fn get_attachment_metadata(&self, id: &str) -> Result<Attachment, JiraError> {
+        let request = self.inner.new_request(self.api_path(&["attachment", id]));
+
+        self.perform_request(request, chipp_http::json::parse_json)
             .await
     }
+
+    /// Streams the file to `writer` without buffering it in memory, the
+    /// writer is returned once the download completes.
+    ///
+    /// Failed requests are retried until the file starts arriving, `writer`
+    /// may have received part of the file on failure.
+    pub async fn download_attachment<W>(
+        &self,
+        attachment: &Attachment,
+        writer: W,
+    ) -> Result<W, JiraError>
+    where
+        W: Write + Send + 'static,
+    {
+        let mut request = self
+            .inner
+            .new_request_with_url(&attachment.content)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+
+        // Credentials are only sent to the Jira origin, e.g. not to the
+        // media service Jira Cloud redirects to.
+        let origin = request.url.origin();
+        let writer = Arc::new(Mutex::new(writer));
+
+        for _ in 0..=attachment::MAX_REDIRECTS {
+            let authorize = request.url.origin() == origin;
+
+            let location = retry(
+                &self.retry_policy,
+                MethodKind::Idempotent,
+                &request.url,
+                self.retry_hook.as_ref(),
+                || async {
+                    let request = clone_request(&request);
+
+                    if !authorize {
+                        return attachment::fetch(request, &self.transport, writer.clone()).await;
+                    }
+
+                    // Authorized per hop, OAuth signs the exact URL.
+                    self.perform_authorized_with(request, |request| {
+                        self.send_with(request, |request| {
+                            attachment::fetch(request, &self.transport, writer.clone())
+                        })
+                    })
+                    .await
+                },
+            )
+            .await?;
+
+            let Some(location) = location else {
+                return match Arc::try_unwrap(writer) {
+                    Ok(writer) => Ok(writer.into_inner().unwrap_or_else(PoisonError::into_inner)),
+                    Err(_) => Err(io::Error::other("download writer still in use").into()),
+                };
+            };
+
+            request = Request::new(location);
+        }
+
+        Err(io::Error::other(format!("more than {} redirects", attachment::MAX_REDIRECTS)).into())
+    }
+
+    pub async fn delete_attachment(&self, id: &str) -> Result<(), JiraError> {
+        let mut request = self.inner.new_request(self.api_path(&["attachment", id]));
+        request.set_method(HttpMethod::Delete);
+
+        self.perform_request(request, chipp_http::parse_void).await
+    }
+
+    pub async fn list_link_types(&self) -> Result<Vec<IssueLinkType>, JiraError> {
+        let request = self.inner.new_request(self.api_path(&["issueLinkType"]));
+
+        let types: IssueLinkTypes = self
+            .perform_request(request, chipp_http::json::parse_json)
+            .await?;
+
+        Ok(types.issue_link_types)
+    }
+
+    /// Links `from` to `to` with the outward description of the type, e.g.
+    /// `create_issue_link("RS-1", "RS-2", "Blocks")` makes RS-1 block RS-2.
+    pub async fn create_issue_link(
+        &self,
+        from: &str,
+        to: &str,
+        type_name: &str,
+    ) -> Result<(), JiraError> {
+        #[derive(Serialize)]
+        struct LinkType<'a> {
+            name: &'a str,
+        }
+
+        // Jira names the sides after the description shown on the other
+        // issue, the source of an outward link is the `inwardIssue`.
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Body<'a> {
+            r#type: LinkType<'a>,
+            inward_issue: KeyRef,
+            outward_issue: KeyRef,
+        }
+
+        let body = Body {
+            r#type: LinkType { name: type_name },
+            inward_issue: KeyRef::new(from),
+            outward_issue: KeyRef::new(to),
+        };
+
+        let mut request = self.inner.new_request(self.api_path(&["issueLink"]));
+        request.set_method(HttpMethod::Post);
+        request.set_json_body(&body);
+
+        self.perform_request(request, chipp_http::parse_void).await
+    }
+
+    pub async fn delete_issue_link(&self, link_id: &str) -> Result<(), JiraError> {
+        let mut request = self
+            .inner
+            .new_request(self.api_path(&["issueLink", link_id]));
+        request.set_method(HttpMethod::Delete);
+
+        self.perform_request(request, chipp_http::parse_void).await
+    }
+}
+
+// Jira throttles per user, so a 429 pauses all requests sharing the limiter.
+fn result_retry_after<R>(result: &Result<R, JiraError>) -> Option<Duration> {
+    match result {
+        Err(error @ JiraError::RateLimited { .. }) => error.retry_after(),
+        _ => None,
+    }
+}
+
+// `Request` isn't `Clone`, a copy is needed to resend it on retries or after
+// renewing the session.
+pub(crate) fn clone_request(request: &Request) -> Request {
+    Request {
+        url: request.url.clone(),
+        method: match request.method {
+            HttpMethod::Get => HttpMethod::Get,
+            HttpMethod::Post => HttpMethod::Post,
+            HttpMethod::Put => HttpMethod::Put,
+            HttpMethod::Delete => HttpMethod::Delete,
+        },
+        headers: request.headers.clone(),
+        form: request.form.clone(),
+        body: request.body.clone(),
+        retry_count: request.retry_count,
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn issue_parser<T>(
+    registry: Arc<FieldRegistry>,
+) -> impl Fn(Request, Response) -> Result<T, chipp_http::Error> + Clone + Send + 'static
+where
+    T: DeserializeOwned,
+{
+    move |req, res| {
+        if !(200..300).contains(&res.status_code) {
+            return Err((req, res).into());
+        }
+
+        let mut value: serde_json::Value = match serde_json::from_slice(&res.body) {
+            Ok(value) => value,
+            Err(err) => return Err((req, err).into()),
+        };
+
+        registry.canonicalize_issues(&mut value);
+        serde_json::from_value(value).map_err(|err| (req, err).into())
+    }
+}
+
+// Jira responds with 400 when every item of a bulk request failed, but the
+// body still lists the per-item errors.
+#[allow(clippy::result_large_err)]
+fn parse_bulk_create_result(
+    req: Request,
+    res: Response,
+) -> Result<BulkCreateResult, chipp_http::Error> {
+    if res.status_code == 400 {
+        if let Ok(result) = serde_json::from_slice(&res.body) {
+            return Ok(result);
+        }
+    }
+
+    chipp_http::json::parse_json(req, res)
 }
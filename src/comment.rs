@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::User;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Comments {
+    pub start_at: u32,
+    pub max_results: u32,
+    pub total: u32,
+
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: String,
+    pub author: Option<User>,
+    pub update_author: Option<User>,
+    #[serde(deserialize_with = "crate::adf::deserialize_text")]
+    pub body: String,
+
+    #[serde(deserialize_with = "crate::date_format::deserialize_date_with_tz")]
+    pub created: DateTime<Utc>,
+
+    #[serde(deserialize_with = "crate::date_format::deserialize_optional_date_with_tz")]
+    #[serde(default)]
+    pub updated: Option<DateTime<Utc>>,
+
+    pub visibility: Option<Visibility>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Visibility {
+    pub r#type: VisibilityType,
+    pub value: String,
+}
+
+impl Visibility {
+    pub fn group<V: ToString>(name: V) -> Visibility {
+        Visibility {
+            r#type: VisibilityType::Group,
+            value: name.to_string(),
+        }
+    }
+
+    pub fn role<V: ToString>(name: V) -> Visibility {
+        Visibility {
+            r#type: VisibilityType::Role,
+            value: name.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VisibilityType {
+    Group,
+    Role,
+}
+
+#[derive(Serialize)]
+pub(crate) struct CommentBody<'a> {
+    pub body: serde_json::Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<&'a Visibility>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn parsing() {
+        let json = json!({
+            "startAt": 0,
+            "maxResults": 1048576,
+            "total": 2,
+            "comments": [
+                {
+                    "self": "https://jira.example.io/rest/api/2/issue/10010/comment/10000",
+                    "id": "10000",
+                    "author": {"key": "JIRAUSER1", "name": "vpupkin", "displayName": "Pupkin, Vasiliy"},
+                    "body": "Looks good to me",
+                    "updateAuthor": {"key": "JIRAUSER1", "name": "vpupkin"},
+                    "created": "2019-10-14T15:59:50.000-0400",
+                    "updated": "2019-10-14T16:10:00.000-0400"
+                },
+                {
+                    "id": "10001",
+                    "author": {"key": "chipp", "name": "chipp"},
+                    "body": "Internal note",
+                    "created": "2019-10-15T09:00:00.000+0000",
+                    "visibility": {"type": "role", "value": "Developers"}
+                }
+            ]
+        });
+
+        let comments: Comments = serde_json::from_value(json).unwrap();
+        assert_eq!(comments.total, 2);
+
+        let first = &comments.comments[0];
+        assert_eq!(first.id, "10000");
+        assert_eq!(first.body, "Looks good to me");
+        assert_eq!(
+            first.author.as_ref().unwrap().name,
+            Some("vpupkin".to_owned())
+        );
+        assert_eq!(
+            first.created,
+            Utc.with_ymd_and_hms(2019, 10, 14, 19, 59, 50).unwrap()
+        );
+        assert_eq!(
+            first.updated,
+            Some(Utc.with_ymd_and_hms(2019, 10, 14, 20, 10, 0).unwrap())
+        );
+        assert_eq!(first.visibility, None);
+
+        let second = &comments.comments[1];
+        assert_eq!(second.updated, None);
+        assert_eq!(second.visibility, Some(Visibility::role("Developers")));
+    }
+
+    #[test]
+    fn body_serialization() {
+        let visibility = Visibility::group("jira-developers");
+        let body = CommentBody {
+            body: json!("Hello"),
+            visibility: Some(&visibility),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            json!({"body": "Hello", "visibility": {"type": "group", "value": "jira-developers"}})
+        );
+
+        let body = CommentBody {
+            body: json!("Hello"),
+            visibility: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            json!({"body": "Hello"})
+        );
+    }
+}
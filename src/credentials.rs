@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+/// Source of the secrets used to authenticate requests to the Jira instance at `domain`.
+pub trait CredentialProvider: Send + Sync {
+    /// Username and password for `AuthType::BasicAuth`.
+    fn user_and_password(&self, domain: &str) -> Result<(String, String), CredentialError>;
+
+    /// Personal access token for `AuthType::AccessToken`.
+    fn access_token(&self, domain: &str) -> Result<String, CredentialError>;
+
+    /// Forgets cached credentials, called when Jira responds with 401.
+    ///
+    /// Returns whether the next call may return different credentials, in
+    /// which case the client retries the request once.
+    fn refresh(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+pub enum CredentialError {
+    /// The provider has no credentials of the requested kind.
+    Missing(String),
+    Io(std::io::Error),
+    /// A credential command exited unsuccessfully.
+    Command(String),
+    /// A configured key couldn't be parsed.
+    InvalidKey(String),
+}
+
+impl fmt::Display for CredentialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialError::Missing(what) => write!(f, "missing credentials: {}", what),
+            CredentialError::Io(error) => write!(f, "can't read credentials: {}", error),
+            CredentialError::Command(error) => write!(f, "credential command failed: {}", error),
+            CredentialError::InvalidKey(error) => write!(f, "invalid key: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for CredentialError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CredentialError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CredentialError {
+    fn from(error: std::io::Error) -> CredentialError {
+        CredentialError::Io(error)
+    }
+}
+
+/// Reads `JIRA_USER`, `JIRA_PASS` and `JIRA_ACCESS_TOKEN` by default.
+#[derive(Clone, Debug)]
+pub struct EnvCredentials {
+    user_var: String,
+    pass_var: String,
+    token_var: String,
+}
+
+impl EnvCredentials {
+    pub fn new() -> EnvCredentials {
+        EnvCredentials::with_names("JIRA_USER", "JIRA_PASS", "JIRA_ACCESS_TOKEN")
+    }
+
+    pub fn with_names<U, P, T>(user_var: U, pass_var: P, token_var: T) -> EnvCredentials
+    where
+        U: ToString,
+        P: ToString,
+        T: ToString,
+    {
+        EnvCredentials {
+            user_var: user_var.to_string(),
+            pass_var: pass_var.to_string(),
+            token_var: token_var.to_string(),
+        }
+    }
+
+    fn var(name: &str) -> Result<String, CredentialError> {
+        std::env::var(name).map_err(|_| CredentialError::Missing(format!("${name}")))
+    }
+}
+
+impl Default for EnvCredentials {
+    fn default() -> EnvCredentials {
+        EnvCredentials::new()
+    }
+}
+
+impl CredentialProvider for EnvCredentials {
+    fn user_and_password(&self, _: &str) -> Result<(String, String), CredentialError> {
+        Ok((Self::var(&self.user_var)?, Self::var(&self.pass_var)?))
+    }
+
+    fn access_token(&self, _: &str) -> Result<String, CredentialError> {
+        Self::var(&self.token_var)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct StaticCredentials {
+    user_and_password: Option<(String, String)>,
+    access_token: Option<String>,
+}
+
+impl StaticCredentials {
+    pub fn user_and_password<U: ToString, P: ToString>(user: U, password: P) -> StaticCredentials {
+        StaticCredentials {
+            user_and_password: Some((user.to_string(), password.to_string())),
+            access_token: None,
+        }
+    }
+
+    pub fn access_token<T: ToString>(token: T) -> StaticCredentials {
+        StaticCredentials {
+            user_and_password: None,
+            access_token: Some(token.to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for StaticCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticCredentials").finish_non_exhaustive()
+    }
+}
+
+impl CredentialProvider for StaticCredentials {
+    fn user_and_password(&self, _: &str) -> Result<(String, String), CredentialError> {
+        self.user_and_password
+            .clone()
+            .ok_or_else(|| CredentialError::Missing("user and password".to_owned()))
+    }
+
+    fn access_token(&self, _: &str) -> Result<String, CredentialError> {
+        self.access_token
+            .clone()
+            .ok_or_else(|| CredentialError::Missing("access token".to_owned()))
+    }
+}
+
+/// Reads a netrc-style file, `~/.netrc` by default.
+///
+/// The `password` of the matching `machine` is used as the access token.
+/// The file is read once per domain until `refresh`.
+#[derive(Clone)]
+pub struct NetrcCredentials {
+    path: PathBuf,
+    entries: Arc<Mutex<HashMap<String, NetrcEntry>>>,
+}
+
+impl NetrcCredentials {
+    pub fn new<P: AsRef<Path>>(path: P) -> NetrcCredentials {
+        NetrcCredentials {
+            path: path.as_ref().to_owned(),
+            entries: Arc::default(),
+        }
+    }
+
+    pub fn home() -> Result<NetrcCredentials, CredentialError> {
+        let home =
+            std::env::var_os("HOME").ok_or_else(|| CredentialError::Missing("$HOME".to_owned()))?;
+
+        Ok(NetrcCredentials::new(Path::new(&home).join(".netrc")))
+    }
+
+    fn entry(&self, domain: &str) -> Result<NetrcEntry, CredentialError> {
+        let mut entries = self.entries.lock().unwrap();
+
+        if let Some(entry) = entries.get(domain) {
+            return Ok(entry.clone());
+        }
+
+        let contents = std::fs::read_to_string(&self.path)?;
+        let entry = parse_netrc(&contents, domain).ok_or_else(|| {
+            CredentialError::Missing(format!("machine {} in {}", domain, self.path.display()))
+        })?;
+
+        entries.insert(domain.to_owned(), entry.clone());
+        Ok(entry)
+    }
+}
+
+impl fmt::Debug for NetrcCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NetrcCredentials")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CredentialProvider for NetrcCredentials {
+    fn user_and_password(&self, domain: &str) -> Result<(String, String), CredentialError> {
+        let entry = self.entry(domain)?;
+        let missing = |what: &str| CredentialError::Missing(format!("{what} for {domain}"));
+
+        Ok((
+            entry.login.ok_or_else(|| missing("login"))?,
+            entry.password.ok_or_else(|| missing("password"))?,
+        ))
+    }
+
+    fn access_token(&self, domain: &str) -> Result<String, CredentialError> {
+        self.entry(domain)?
+            .password
+            .ok_or_else(|| CredentialError::Missing(format!("password for {domain}")))
+    }
+
+    fn refresh(&self) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let cached = !entries.is_empty();
+        entries.clear();
+        cached
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct NetrcEntry {
+    login: Option<String>,
+    password: Option<String>,
+}
+
+fn parse_netrc(contents: &str, domain: &str) -> Option<NetrcEntry> {
+    let mut tokens = contents.split_whitespace();
+
+    let mut matched: Option<NetrcEntry> = None;
+    let mut default: Option<NetrcEntry> = None;
+    let mut current: Option<&mut NetrcEntry> = None;
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" => {
+                if matched.is_some() {
+                    break;
+                }
+
+                if tokens.next() == Some(domain) {
+                    current = Some(matched.insert(NetrcEntry::default()));
+                } else {
+                    current = None;
+                }
+            }
+            "default" => {
+                if matched.is_some() {
+                    break;
+                }
+
+                current = Some(default.insert(NetrcEntry::default()));
+            }
+            "login" | "password" => {
+                let value = tokens.next().map(ToOwned::to_owned);
+
+                if let Some(entry) = current.as_mut() {
+                    if token == "login" {
+                        entry.login = value;
+                    } else {
+                        entry.password = value;
+                    }
+                }
+            }
+            "account" => {
+                tokens.next();
+            }
+            _ => (),
+        }
+    }
+
+    matched.or(default)
+}
+
+/// Runs a command and reads credentials from its standard output.
+///
+/// The access token is the whole trimmed output; user and password are
+/// expected on the first two lines. The command runs once until `refresh`.
+#[derive(Clone)]
+pub struct CommandCredentials {
+    program: String,
+    args: Vec<String>,
+    output: Arc<Mutex<Option<String>>>,
+}
+
+impl CommandCredentials {
+    pub fn new<P, I, A>(program: P, args: I) -> CommandCredentials
+    where
+        P: ToString,
+        I: IntoIterator<Item = A>,
+        A: ToString,
+    {
+        CommandCredentials {
+            program: program.to_string(),
+            args: args.into_iter().map(|arg| arg.to_string()).collect(),
+            output: Arc::default(),
+        }
+    }
+
+    fn output(&self) -> Result<String, CredentialError> {
+        let mut cached = self.output.lock().unwrap();
+
+        if let Some(output) = cached.as_ref() {
+            return Ok(output.clone());
+        }
+
+        let output = self.run()?;
+        *cached = Some(output.clone());
+        Ok(output)
+    }
+
+    fn run(&self) -> Result<String, CredentialError> {
+        let output = Command::new(&self.program).args(&self.args).output()?;
+
+        if !output.status.success() {
+            return Err(CredentialError::Command(format!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        String::from_utf8(output.stdout).map_err(|_| {
+            CredentialError::Command(format!("{} printed invalid UTF-8", self.program))
+        })
+    }
+}
+
+impl fmt::Debug for CommandCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandCredentials")
+            .field("program", &self.program)
+            .field("args", &self.args)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CredentialProvider for CommandCredentials {
+    fn user_and_password(&self, _: &str) -> Result<(String, String), CredentialError> {
+        let output = self.output()?;
+        let mut lines = output.lines();
+
+        match (lines.next(), lines.next()) {
+            (Some(user), Some(password)) => Ok((user.trim().to_owned(), password.to_owned())),
+            _ => Err(CredentialError::Command(format!(
+                "{} should print user and password on separate lines",
+                self.program
+            ))),
+        }
+    }
+
+    fn access_token(&self, _: &str) -> Result<String, CredentialError> {
+        let token = self.output()?.trim().to_owned();
+
+        if token.is_empty() {
+            Err(CredentialError::Missing(format!(
+                "access token from {}",
+                self.program
+            )))
+        } else {
+            Ok(token)
+        }
+    }
+
+    fn refresh(&self) -> bool {
+        self.output.lock().unwrap().take().is_some()
+    }
+}
+
+/// Reads credentials from the macOS keychain.
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeychainCredentials;
+
+#[cfg(target_os = "macos")]
+impl CredentialProvider for KeychainCredentials {
+    fn user_and_password(&self, domain: &str) -> Result<(String, String), CredentialError> {
+        Ok(chipp_auth::user_and_password(domain))
+    }
+
+    fn access_token(&self, domain: &str) -> Result<String, CredentialError> {
+        Ok(chipp_auth::token(domain, "access_token"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn netrc_parsing() {
+        let netrc = "
+            machine github.com login octocat password gh-token
+            machine jira.example.io
+                login chipp
+                password secret
+            default login anonymous password guest
+        ";
+
+        assert_eq!(
+            parse_netrc(netrc, "jira.example.io"),
+            Some(NetrcEntry {
+                login: Some("chipp".to_owned()),
+                password: Some("secret".to_owned()),
+            })
+        );
+
+        assert_eq!(
+            parse_netrc(netrc, "jira.staging.example.io"),
+            Some(NetrcEntry {
+                login: Some("anonymous".to_owned()),
+                password: Some("guest".to_owned()),
+            })
+        );
+
+        assert_eq!(
+            parse_netrc("machine github.com login octocat", "jira.example.io"),
+            None
+        );
+    }
+
+    #[test]
+    fn env_credentials() {
+        std::env::set_var("JIRA_API_TEST_USER", "chipp");
+        std::env::set_var("JIRA_API_TEST_PASS", "secret");
+
+        let credentials = EnvCredentials::with_names(
+            "JIRA_API_TEST_USER",
+            "JIRA_API_TEST_PASS",
+            "JIRA_API_TEST_TOKEN",
+        );
+
+        assert_eq!(
+            credentials.user_and_password("jira.example.io").unwrap(),
+            ("chipp".to_owned(), "secret".to_owned())
+        );
+        assert!(matches!(
+            credentials.access_token("jira.example.io"),
+            Err(CredentialError::Missing(var)) if var == "$JIRA_API_TEST_TOKEN"
+        ));
+    }
+
+    #[test]
+    fn static_credentials() {
+        let credentials = StaticCredentials::access_token("token");
+
+        assert_eq!(
+            credentials.access_token("jira.example.io").unwrap(),
+            "token"
+        );
+        assert!(credentials.user_and_password("jira.example.io").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_credentials() {
+        let credentials = CommandCredentials::new("printf", ["chipp\\nsecret\\n"]);
+
+        assert_eq!(
+            credentials.user_and_password("jira.example.io").unwrap(),
+            ("chipp".to_owned(), "secret".to_owned())
+        );
+        assert_eq!(
+            credentials.access_token("jira.example.io").unwrap(),
+            "chipp\nsecret"
+        );
+
+        let credentials = CommandCredentials::new("false", Vec::<String>::new());
+        assert!(matches!(
+            credentials.access_token("jira.example.io"),
+            Err(CredentialError::Command(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cached_credentials() {
+        let dir = std::env::temp_dir().join(format!("jira-api-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let runs = dir.join("runs");
+        let _ = std::fs::remove_file(&runs);
+
+        let credentials = CommandCredentials::new(
+            "sh",
+            [
+                "-c".to_owned(),
+                format!("echo >> {}; echo token", runs.display()),
+            ],
+        );
+        let run_count = || std::fs::read_to_string(&runs).unwrap().lines().count();
+
+        for _ in 0..3 {
+            assert_eq!(
+                credentials.access_token("jira.example.io").unwrap(),
+                "token"
+            );
+        }
+        assert_eq!(run_count(), 1);
+
+        assert!(credentials.refresh());
+        assert!(!credentials.refresh());
+        credentials.access_token("jira.example.io").unwrap();
+        assert_eq!(run_count(), 2);
+
+        let netrc = dir.join("netrc");
+        std::fs::write(&netrc, "machine jira.example.io password first").unwrap();
+        let credentials = NetrcCredentials::new(&netrc);
+        assert_eq!(
+            credentials.access_token("jira.example.io").unwrap(),
+            "first"
+        );
+
+        std::fs::write(&netrc, "machine jira.example.io password second").unwrap();
+        assert_eq!(
+            credentials.access_token("jira.example.io").unwrap(),
+            "first"
+        );
+
+        assert!(credentials.refresh());
+        assert_eq!(
+            credentials.access_token("jira.example.io").unwrap(),
+            "second"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,4 +2,7 @@ mod with_tz;
 pub use with_tz::{deserialize_date_with_tz, deserialize_optional_date_with_tz};
 
 mod without_tz;
-pub use without_tz::{deserialize_date_without_tz, deserialize_optional_date_without_tz};
+pub use without_tz::{
+    deserialize_date_without_tz, deserialize_optional_date_without_tz,
+    serialize_optional_date_without_tz,
+};
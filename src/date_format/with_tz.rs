@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{self, de, Deserializer};
 use std::fmt;
 
-const FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
+const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
 
 pub fn deserialize_optional_date_with_tz<'de, D>(
     deserializer: D,
@@ -55,7 +55,7 @@ impl<'de> de::Visitor<'de> for DateTimeFromCustomFormatVisitor {
     where
         E: de::Error,
     {
-        DateTime::parse_from_str(&value, FORMAT)
+        DateTime::parse_from_str(value, FORMAT)
             .map_err(serde::de::Error::custom)
             .map(|d| d.into())
     }
@@ -70,7 +70,7 @@ mod tests {
     fn date_format() {
         let date = FixedOffset::west_opt(4 * 3600)
             .unwrap()
-            .with_ymd_and_hms(2020, 03, 10, 10, 20, 50)
+            .with_ymd_and_hms(2020, 3, 10, 10, 20, 50)
             .unwrap()
             + Duration::milliseconds(730);
 
@@ -1,8 +1,8 @@
-use chrono::{DateTime, TimeZone, Utc};
-use serde::{self, de, Deserializer};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{self, de, Deserializer, Serializer};
 use std::fmt;
 
-const FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
 
 pub fn deserialize_optional_date_without_tz<'de, D>(
     deserializer: D,
@@ -36,6 +36,19 @@ impl<'de> de::Visitor<'de> for OptionalDateTimeFromCustomFormatVisitor {
     }
 }
 
+pub fn serialize_optional_date_without_tz<S>(
+    date: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match date {
+        Some(date) => serializer.collect_str(&date.format(FORMAT)),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[allow(dead_code)]
 pub fn deserialize_date_without_tz<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
 where
@@ -56,9 +69,9 @@ impl<'de> de::Visitor<'de> for DateTimeFromCustomFormatVisitor {
     where
         E: de::Error,
     {
-        Utc.datetime_from_str(&value, FORMAT)
+        NaiveDateTime::parse_from_str(value, FORMAT)
             .map_err(serde::de::Error::custom)
-            .map(|d| d.into())
+            .map(|d| d.and_utc())
     }
 }
 
@@ -70,13 +83,14 @@ mod tests {
     #[test]
     fn date_format() {
         let date =
-            Utc.with_ymd_and_hms(2020, 03, 10, 10, 20, 50).unwrap() + Duration::milliseconds(730);
+            Utc.with_ymd_and_hms(2020, 3, 10, 10, 20, 50).unwrap() + Duration::milliseconds(730);
 
         assert_eq!(date.format(FORMAT).to_string(), "2020-03-10T10:20:50.730Z");
 
         assert_eq!(
-            Utc.datetime_from_str("2020-03-10T10:20:50.730Z", FORMAT)
-                .unwrap(),
+            NaiveDateTime::parse_from_str("2020-03-10T10:20:50.730Z", FORMAT)
+                .unwrap()
+                .and_utc(),
             date.with_timezone(&Utc)
         );
     }
@@ -103,6 +117,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_optional_date() {
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        struct Test {
+            #[serde(serialize_with = "super::serialize_optional_date_without_tz")]
+            date: Option<DateTime<Utc>>,
+        }
+
+        let test = Test {
+            date: Some(
+                Utc.with_ymd_and_hms(2019, 10, 14, 15, 59, 50).unwrap()
+                    + Duration::milliseconds(12),
+            ),
+        };
+        assert_eq!(
+            serde_json::to_string(&test).unwrap(),
+            r#"{"date":"2019-10-14T15:59:50.012Z"}"#
+        );
+
+        let test = Test { date: None };
+        assert_eq!(serde_json::to_string(&test).unwrap(), r#"{"date":null}"#);
+    }
+
     #[test]
     fn deserialize_date() {
         use serde::Deserialize;
@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use chipp_http::{ErrorKind, Response};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::credentials::CredentialError;
+
+#[derive(Debug)]
+pub enum JiraError {
+    /// 400, Jira rejected the request, e.g. because of an invalid field value.
+    Validation(ErrorCollection),
+    /// 401, missing or wrong credentials.
+    Unauthorized(ErrorCollection),
+    /// 403, the user is authenticated but lacks a permission.
+    Forbidden(ErrorCollection),
+    /// 404, the entity doesn't exist or isn't visible to the user.
+    NotFound(ErrorCollection),
+    /// 429, Jira throttled the request.
+    RateLimited { retry_after: Option<Duration> },
+    /// 503, Jira is temporarily unavailable.
+    ServiceUnavailable { retry_after: Option<Duration> },
+    /// Any other unsuccessful status code.
+    Http {
+        status: u32,
+        errors: ErrorCollection,
+    },
+    /// The response body doesn't match the expected model.
+    Parse(chipp_http::Error),
+    /// The request didn't complete, e.g. on connection failures or timeouts.
+    Transport(chipp_http::Error),
+    /// Credentials couldn't be obtained, the request wasn't sent.
+    Credentials(CredentialError),
+    /// Reading an upload or writing a download failed.
+    Io(std::io::Error),
+}
+
+impl JiraError {
+    pub fn status(&self) -> Option<u32> {
+        match self {
+            JiraError::Validation(_) => Some(400),
+            JiraError::Unauthorized(_) => Some(401),
+            JiraError::Forbidden(_) => Some(403),
+            JiraError::NotFound(_) => Some(404),
+            JiraError::RateLimited { .. } => Some(429),
+            JiraError::ServiceUnavailable { .. } => Some(503),
+            JiraError::Http { status, .. } => Some(*status),
+            JiraError::Parse(_)
+            | JiraError::Transport(_)
+            | JiraError::Credentials(_)
+            | JiraError::Io(_) => None,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            JiraError::RateLimited { retry_after }
+            | JiraError::ServiceUnavailable { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    pub fn errors(&self) -> Option<&ErrorCollection> {
+        match self {
+            JiraError::Validation(errors)
+            | JiraError::Unauthorized(errors)
+            | JiraError::Forbidden(errors)
+            | JiraError::NotFound(errors)
+            | JiraError::Http { errors, .. } => Some(errors),
+            _ => None,
+        }
+    }
+
+    fn from_response(response: &Response) -> JiraError {
+        let errors = || serde_json::from_slice(&response.body).unwrap_or_default();
+
+        match response.status_code {
+            400 => JiraError::Validation(errors()),
+            401 => JiraError::Unauthorized(errors()),
+            403 => JiraError::Forbidden(errors()),
+            404 => JiraError::NotFound(errors()),
+            429 => JiraError::RateLimited {
+                retry_after: retry_after(response),
+            },
+            503 => JiraError::ServiceUnavailable {
+                retry_after: retry_after(response),
+            },
+            status => JiraError::Http {
+                status,
+                errors: errors(),
+            },
+        }
+    }
+}
+
+impl From<chipp_http::Error> for JiraError {
+    fn from(error: chipp_http::Error) -> JiraError {
+        match &error.kind {
+            ErrorKind::HttpError(response) => JiraError::from_response(response),
+            ErrorKind::JsonParseError(_) => JiraError::Parse(error),
+            ErrorKind::CurlError(_) => JiraError::Transport(error),
+        }
+    }
+}
+
+impl From<CredentialError> for JiraError {
+    fn from(error: CredentialError) -> JiraError {
+        JiraError::Credentials(error)
+    }
+}
+
+impl From<std::io::Error> for JiraError {
+    fn from(error: std::io::Error) -> JiraError {
+        JiraError::Io(error)
+    }
+}
+
+impl fmt::Display for JiraError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JiraError::Validation(errors) => write!(f, "validation failed: {}", errors),
+            JiraError::Unauthorized(errors) => write!(f, "unauthorized: {}", errors),
+            JiraError::Forbidden(errors) => write!(f, "forbidden: {}", errors),
+            JiraError::NotFound(errors) => write!(f, "not found: {}", errors),
+            JiraError::RateLimited { retry_after } => match retry_after {
+                Some(delay) => write!(f, "rate limited, retry after {}s", delay.as_secs()),
+                None => write!(f, "rate limited"),
+            },
+            JiraError::ServiceUnavailable { retry_after } => match retry_after {
+                Some(delay) => write!(f, "service unavailable, retry after {}s", delay.as_secs()),
+                None => write!(f, "service unavailable"),
+            },
+            JiraError::Http { status, errors } => write!(f, "HTTP {}: {}", status, errors),
+            JiraError::Parse(error) => write!(f, "invalid response: {}", error),
+            JiraError::Transport(error) => write!(f, "transport error: {}", error),
+            JiraError::Credentials(error) => write!(f, "{}", error),
+            JiraError::Io(error) => write!(f, "I/O error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for JiraError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JiraError::Parse(error) | JiraError::Transport(error) => Some(error),
+            JiraError::Credentials(error) => Some(error),
+            JiraError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Jira's standard error body: general messages plus per-field messages.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorCollection {
+    #[serde(default)]
+    pub error_messages: Vec<String>,
+    #[serde(default)]
+    pub errors: HashMap<String, String>,
+}
+
+impl fmt::Display for ErrorCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = self.errors.iter().collect::<Vec<_>>();
+        fields.sort();
+
+        let messages = self
+            .error_messages
+            .iter()
+            .cloned()
+            .chain(
+                fields
+                    .into_iter()
+                    .map(|(field, message)| format!("{field}: {message}")),
+            )
+            .collect::<Vec<_>>();
+
+        if messages.is_empty() {
+            write!(f, "no details")
+        } else {
+            write!(f, "{}", messages.join("; "))
+        }
+    }
+}
+
+pub(crate) fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers.iter().rev().find_map(|header| {
+        let (name, value) = header.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("retry-after")
+            .then(|| value.trim())
+    })?;
+
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chipp_http::Request;
+    use url::Url;
+
+    fn http_error(status_code: u32, headers: &[&str], body: &str) -> JiraError {
+        let request = Request::new(Url::parse("https://jira.example.io/rest/api/2/issue").unwrap());
+        let response = Response {
+            status_code,
+            body: body.as_bytes().to_vec(),
+            headers: headers.iter().map(ToString::to_string).collect(),
+        };
+
+        chipp_http::Error::from((request, response)).into()
+    }
+
+    #[test]
+    fn validation_error() {
+        let error = http_error(
+            400,
+            &["HTTP/1.1 400", "Content-Type: application/json"],
+            r#"{"errorMessages":[],"errors":{"customfield_10182":"Story Points must be a number"}}"#,
+        );
+
+        let errors = match &error {
+            JiraError::Validation(errors) => errors,
+            error => panic!("unexpected {:?}", error),
+        };
+
+        assert_eq!(
+            errors.errors.get("customfield_10182"),
+            Some(&"Story Points must be a number".to_owned())
+        );
+        assert_eq!(
+            error.to_string(),
+            "validation failed: customfield_10182: Story Points must be a number"
+        );
+    }
+
+    #[test]
+    fn status_mapping() {
+        assert!(matches!(
+            http_error(401, &[], ""),
+            JiraError::Unauthorized(_)
+        ));
+        assert!(matches!(
+            http_error(404, &[], r#"{"errorMessages":["Issue Does Not Exist"],"errors":{}}"#),
+            JiraError::NotFound(errors) if errors.error_messages == ["Issue Does Not Exist"]
+        ));
+        assert!(matches!(
+            http_error(403, &[], "<html></html>"),
+            JiraError::Forbidden(errors) if errors == ErrorCollection::default()
+        ));
+        assert!(matches!(
+            http_error(502, &[], ""),
+            JiraError::Http { status: 502, .. }
+        ));
+    }
+
+    #[test]
+    fn rate_limited() {
+        let error = http_error(429, &["HTTP/1.1 429", "Retry-After: 12"], "");
+
+        assert!(matches!(error, JiraError::RateLimited { .. }));
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(12)));
+        assert_eq!(error.status(), Some(429));
+
+        let error = http_error(503, &["retry-after: Wed, 21 Oct 2015 07:28:00 GMT"], "");
+        assert!(matches!(error, JiraError::ServiceUnavailable { .. }));
+        assert_eq!(error.retry_after(), None);
+    }
+}
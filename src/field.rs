@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldMeta {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub custom: bool,
+    #[serde(default)]
+    pub clause_names: Vec<String>,
+    pub schema: Option<FieldSchema>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSchema {
+    pub r#type: String,
+    pub items: Option<String>,
+    pub custom: Option<String>,
+    pub custom_id: Option<u64>,
+}
+
+/// Logical custom fields whose ids differ between Jira instances.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CustomField {
+    StoryPoints,
+    Sprint,
+    EpicLink,
+    Team,
+}
+
+impl CustomField {
+    pub const ALL: [CustomField; 4] = [
+        CustomField::StoryPoints,
+        CustomField::Sprint,
+        CustomField::EpicLink,
+        CustomField::Team,
+    ];
+
+    /// Field names this logical field is usually known under.
+    pub fn names(self) -> &'static [&'static str] {
+        match self {
+            CustomField::StoryPoints => &["Story Points", "Story point estimate"],
+            CustomField::Sprint => &["Sprint"],
+            CustomField::EpicLink => &["Epic Link"],
+            CustomField::Team => &["Team"],
+        }
+    }
+
+    fn schema_type(self) -> Option<&'static str> {
+        match self {
+            CustomField::StoryPoints => None,
+            CustomField::Sprint => Some("com.pyxis.greenhopper.jira:gh-sprint"),
+            CustomField::EpicLink => Some("com.pyxis.greenhopper.jira:gh-epic-link"),
+            CustomField::Team => Some("com.atlassian.teams:rm-teams-custom-field-team"),
+        }
+    }
+
+    /// The key `issue::Fields`, `issue::ModifyFields` and `issue::CreateFields`
+    /// (de)serialize the field under. It can't be a Jira field id, so the
+    /// registry can move values from and to the instance id without
+    /// touching any real field.
+    pub(crate) fn key(self) -> Option<&'static str> {
+        match self {
+            CustomField::StoryPoints => Some("$storyPoints"),
+            CustomField::Sprint => Some("$sprint"),
+            CustomField::EpicLink | CustomField::Team => None,
+        }
+    }
+
+    /// The ids used before the registry existed, kept until the instance
+    /// fields are loaded.
+    fn legacy_id(self) -> Option<&'static str> {
+        match self {
+            CustomField::StoryPoints => Some("customfield_10182"),
+            CustomField::Sprint => Some("customfield_10231"),
+            CustomField::EpicLink | CustomField::Team => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldRegistry {
+    ids: HashMap<CustomField, String>,
+    fields: Vec<FieldMeta>,
+}
+
+impl Default for FieldRegistry {
+    fn default() -> FieldRegistry {
+        let ids = CustomField::ALL
+            .into_iter()
+            .filter_map(|field| Some((field, field.legacy_id()?.to_owned())))
+            .collect();
+
+        FieldRegistry {
+            ids,
+            fields: vec![],
+        }
+    }
+}
+
+impl FieldRegistry {
+    /// Builds a registry from the instance field list returned by `/api/2/field`.
+    pub fn from_fields(fields: Vec<FieldMeta>) -> FieldRegistry {
+        let mut ids = HashMap::new();
+
+        for logical in CustomField::ALL {
+            let by_schema = logical.schema_type().and_then(|schema_type| {
+                fields.iter().find(|field| {
+                    let custom = field.schema.as_ref().and_then(|s| s.custom.as_deref());
+                    custom == Some(schema_type)
+                })
+            });
+
+            let by_name = || {
+                fields.iter().find(|field| {
+                    logical
+                        .names()
+                        .iter()
+                        .any(|name| field.name.eq_ignore_ascii_case(name))
+                })
+            };
+
+            if let Some(field) = by_schema.or_else(by_name) {
+                ids.insert(logical, field.id.clone());
+            }
+        }
+
+        FieldRegistry { ids, fields }
+    }
+
+    pub fn id(&self, field: CustomField) -> Option<&str> {
+        self.ids.get(&field).map(String::as_str)
+    }
+
+    pub fn set_id<I: ToString>(&mut self, field: CustomField, id: I) {
+        self.ids.insert(field, id.to_string());
+    }
+
+    pub fn id_by_name(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| field.name.eq_ignore_ascii_case(name))
+            .map(|field| field.id.as_str())
+    }
+
+    pub fn fields(&self) -> &[FieldMeta] {
+        &self.fields
+    }
+
+    fn keyed(&self) -> impl Iterator<Item = (&'static str, Option<&str>)> {
+        CustomField::ALL
+            .into_iter()
+            .filter_map(|field| Some((field.key()?, self.id(field))))
+    }
+
+    /// Copies the instance values of logical fields to the keys
+    /// `issue::Fields` reads them from, the original ids stay as they are.
+    ///
+    /// Accepts a single issue or a search page with an `issues` array.
+    pub(crate) fn canonicalize_issues(&self, value: &mut Value) {
+        if let Some(issues) = value.get_mut("issues").and_then(Value::as_array_mut) {
+            for issue in issues {
+                self.canonicalize_issues(issue);
+            }
+        }
+
+        if let Some(fields) = value.get_mut("fields").and_then(Value::as_object_mut) {
+            self.canonicalize(fields);
+        }
+    }
+
+    /// Keys of unresolved or missing fields are set to `null`, so
+    /// `issue::Fields` doesn't fall back to the legacy ids.
+    pub(crate) fn canonicalize(&self, fields: &mut Map<String, Value>) {
+        for (key, id) in self.keyed() {
+            let value = id.and_then(|id| fields.get(id)).cloned();
+            fields.insert(key.to_owned(), value.unwrap_or(Value::Null));
+        }
+    }
+
+    /// Fills keys of logical fields that weren't canonicalized from their
+    /// legacy ids, e.g. in webhook bodies or cached JSON.
+    pub(crate) fn canonicalize_legacy(fields: &mut Map<String, Value>) {
+        for field in CustomField::ALL {
+            let (Some(key), Some(id)) = (field.key(), field.legacy_id()) else {
+                continue;
+            };
+
+            if !fields.contains_key(key) {
+                if let Some(value) = fields.get(id).cloned() {
+                    fields.insert(key.to_owned(), value);
+                }
+            }
+        }
+    }
+
+    /// Moves logical fields set through `issue::ModifyFields` or
+    /// `issue::CreateFields` to their instance ids.
+    ///
+    /// Fields the registry can't resolve are sent as they are, so Jira
+    /// rejects them instead of the value getting lost.
+    pub(crate) fn localize(&self, fields: &mut Map<String, Value>) {
+        for (key, id) in self.keyed() {
+            if let Some(id) = id {
+                if let Some(value) = fields.remove(key) {
+                    fields.insert(id.to_owned(), value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn instance_fields() -> Vec<FieldMeta> {
+        let json = json!([
+            {
+                "id": "summary",
+                "name": "Summary",
+                "custom": false,
+                "clauseNames": ["summary"],
+                "schema": {"type": "string", "system": "summary"}
+            },
+            {
+                "id": "customfield_10002",
+                "name": "Story Points",
+                "custom": true,
+                "clauseNames": ["cf[10002]", "Story Points"],
+                "schema": {
+                    "type": "number",
+                    "custom": "com.atlassian.jira.plugin.system.customfieldtypes:float",
+                    "customId": 10002
+                }
+            },
+            {
+                "id": "customfield_10100",
+                "name": "Iteration",
+                "custom": true,
+                "schema": {
+                    "type": "array",
+                    "items": "string",
+                    "custom": "com.pyxis.greenhopper.jira:gh-sprint",
+                    "customId": 10100
+                }
+            },
+            {
+                "id": "customfield_10101",
+                "name": "Epic Link",
+                "custom": true,
+                "schema": {
+                    "type": "any",
+                    "custom": "com.pyxis.greenhopper.jira:gh-epic-link",
+                    "customId": 10101
+                }
+            }
+        ]);
+
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn discovery() {
+        let registry = FieldRegistry::from_fields(instance_fields());
+
+        assert_eq!(
+            registry.id(CustomField::StoryPoints),
+            Some("customfield_10002")
+        );
+        assert_eq!(registry.id(CustomField::Sprint), Some("customfield_10100"));
+        assert_eq!(
+            registry.id(CustomField::EpicLink),
+            Some("customfield_10101")
+        );
+        assert_eq!(registry.id(CustomField::Team), None);
+        assert_eq!(registry.id_by_name("iteration"), Some("customfield_10100"));
+    }
+
+    #[test]
+    fn default_registry_keeps_legacy_ids() {
+        let registry = FieldRegistry::default();
+
+        assert_eq!(
+            registry.id(CustomField::StoryPoints),
+            Some("customfield_10182")
+        );
+        assert_eq!(registry.id(CustomField::Sprint), Some("customfield_10231"));
+
+        let mut value = json!({"fields": {"customfield_10182": 3.0}});
+        registry.canonicalize_issues(&mut value);
+        assert_eq!(
+            value,
+            json!({"fields": {"customfield_10182": 3.0, "$storyPoints": 3.0, "$sprint": null}})
+        );
+    }
+
+    #[test]
+    fn canonicalize_and_localize() {
+        let registry = FieldRegistry::from_fields(instance_fields());
+
+        let mut value = json!({
+            "issues": [{
+                "fields": {
+                    "customfield_10002": 5.0,
+                    "customfield_10182": "unrelated field",
+                    "customfield_10100": ["sprint"]
+                }
+            }]
+        });
+
+        registry.canonicalize_issues(&mut value);
+
+        // The unrelated field under a legacy id is left alone.
+        assert_eq!(
+            value,
+            json!({
+                "issues": [{
+                    "fields": {
+                        "customfield_10002": 5.0,
+                        "customfield_10182": "unrelated field",
+                        "customfield_10100": ["sprint"],
+                        "$storyPoints": 5.0,
+                        "$sprint": ["sprint"]
+                    }
+                }]
+            })
+        );
+
+        let mut fields = json!({
+            "$storyPoints": 8.0,
+            "customfield_10182": "set by id",
+            "summary": "Test"
+        });
+        registry.localize(fields.as_object_mut().unwrap());
+
+        assert_eq!(
+            fields,
+            json!({
+                "customfield_10002": 8.0,
+                "customfield_10182": "set by id",
+                "summary": "Test"
+            })
+        );
+
+        // Unresolved, Jira reports it rather than the value being dropped.
+        let registry = FieldRegistry::from_fields(vec![]);
+        let mut fields = json!({"$sprint": [1]});
+        registry.localize(fields.as_object_mut().unwrap());
+
+        assert_eq!(fields, json!({"$sprint": [1]}));
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Write};
+
+use crate::issue::{Issue, IssueLinkType};
+
+/// Issues and the links between them, built from `Fields::issue_links`,
+/// `parent` and `subtasks`.
+///
+/// Dependency analysis only follows "blocks" links: an issue has to be done
+/// before the issues it blocks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IssueGraph {
+    // Issues referenced by a link but not added themselves have no value.
+    nodes: BTreeMap<String, Option<Issue>>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EdgeKind {
+    /// `from` relates to `to` with the outward description, e.g. `from` blocks `to`.
+    Link(IssueLinkType),
+    /// `to` is a subtask of `from`.
+    Subtask,
+}
+
+impl Edge {
+    pub fn is_blocking(&self) -> bool {
+        match &self.kind {
+            EdgeKind::Link(link_type) => {
+                link_type.name.eq_ignore_ascii_case("blocks")
+                    || link_type.outward.eq_ignore_ascii_case("blocks")
+            }
+            EdgeKind::Subtask => false,
+        }
+    }
+}
+
+/// Blocking links form a cycle between `issues`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError {
+    pub issues: Vec<String>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "issues block each other: {}", self.issues.join(" -> "))
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CriticalPath {
+    pub issues: Vec<String>,
+    /// Sum of the weights of `issues`.
+    pub weight: u64,
+}
+
+impl IssueGraph {
+    pub fn new() -> IssueGraph {
+        IssueGraph::default()
+    }
+
+    /// Adds the issue and its links, replacing a previously added copy.
+    pub fn add_issue(&mut self, issue: Issue) {
+        let key = issue.key.clone();
+        let fields = &issue.fields;
+
+        for link in fields.issue_links.iter().flatten() {
+            if let Some(outward) = &link.outward_issue {
+                self.add_edge(&key, &outward.key, EdgeKind::Link(link.r#type.clone()));
+            }
+
+            if let Some(inward) = &link.inward_issue {
+                self.add_edge(&inward.key, &key, EdgeKind::Link(link.r#type.clone()));
+            }
+        }
+
+        if let Some(parent) = &fields.parent {
+            self.add_edge(&parent.key, &key, EdgeKind::Subtask);
+        }
+
+        for subtask in fields.subtasks.iter().flatten() {
+            self.add_edge(&key, &subtask.key, EdgeKind::Subtask);
+        }
+
+        self.nodes.insert(key, Some(issue));
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, kind: EdgeKind) {
+        let edge = Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind,
+        };
+
+        // Both ends of a link list it.
+        if !self.edges.contains(&edge) {
+            self.nodes.entry(edge.from.clone()).or_default();
+            self.nodes.entry(edge.to.clone()).or_default();
+            self.edges.push(edge);
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.nodes.contains_key(key)
+    }
+
+    /// `None` for issues only known from links of other issues.
+    pub fn issue(&self, key: &str) -> Option<&Issue> {
+        self.nodes.get(key).and_then(Option::as_ref)
+    }
+
+    /// All keys in the graph, sorted.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.nodes.keys().map(String::as_str)
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Issues linked to `key` in either direction.
+    pub fn neighbours<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.edges.iter().filter_map(move |edge| {
+            if edge.from == key {
+                Some(edge.to.as_str())
+            } else if edge.to == key {
+                Some(edge.from.as_str())
+            } else {
+                None
+            }
+        })
+    }
+
+    // Blocked issues by blocking issue, every key is present.
+    fn blocking(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+        let mut blocking = self
+            .keys()
+            .map(|key| (key, BTreeSet::new()))
+            .collect::<BTreeMap<_, _>>();
+
+        for edge in self.edges.iter().filter(|edge| edge.is_blocking()) {
+            blocking
+                .get_mut(edge.from.as_str())
+                .unwrap()
+                .insert(edge.to.as_str());
+        }
+
+        blocking
+    }
+
+    /// Groups of issues blocking each other, found with Tarjan's algorithm.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        struct Tarjan<'a> {
+            blocking: BTreeMap<&'a str, BTreeSet<&'a str>>,
+            index: HashMap<&'a str, usize>,
+            low_link: HashMap<&'a str, usize>,
+            stack: Vec<&'a str>,
+            cycles: Vec<Vec<String>>,
+        }
+
+        impl<'a> Tarjan<'a> {
+            fn visit(&mut self, key: &'a str) {
+                let index = self.index.len();
+                self.index.insert(key, index);
+                self.low_link.insert(key, index);
+                self.stack.push(key);
+
+                for blocked in self.blocking[key].clone() {
+                    if !self.index.contains_key(blocked) {
+                        self.visit(blocked);
+                        let low_link = self.low_link[key].min(self.low_link[blocked]);
+                        self.low_link.insert(key, low_link);
+                    } else if self.stack.contains(&blocked) {
+                        let low_link = self.low_link[key].min(self.index[blocked]);
+                        self.low_link.insert(key, low_link);
+                    }
+                }
+
+                if self.low_link[key] == index {
+                    let start = self.stack.iter().rposition(|item| *item == key).unwrap();
+                    let component = self.stack.split_off(start);
+
+                    if component.len() > 1 || self.blocking[key].contains(key) {
+                        self.cycles
+                            .push(component.into_iter().map(ToOwned::to_owned).collect());
+                    }
+                }
+            }
+        }
+
+        let mut tarjan = Tarjan {
+            blocking: self.blocking(),
+            index: HashMap::new(),
+            low_link: HashMap::new(),
+            stack: vec![],
+            cycles: vec![],
+        };
+
+        for key in self.keys() {
+            if !tarjan.index.contains_key(key) {
+                tarjan.visit(key);
+            }
+        }
+
+        tarjan.cycles
+    }
+
+    /// Every issue after the issues blocking it, ties are broken by key.
+    pub fn topological_order(&self) -> Result<Vec<&str>, CycleError> {
+        let blocking = self.blocking();
+
+        let mut blocked_by = self
+            .keys()
+            .map(|key| (key, 0))
+            .collect::<BTreeMap<_, usize>>();
+
+        for blocked in blocking.values().flatten() {
+            *blocked_by.get_mut(blocked).unwrap() += 1;
+        }
+
+        let mut ready = blocked_by
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(key, _)| *key)
+            .collect::<BTreeSet<_>>();
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while let Some(key) = ready.pop_first() {
+            order.push(key);
+
+            for blocked in &blocking[key] {
+                let count = blocked_by.get_mut(blocked).unwrap();
+                *count -= 1;
+
+                if *count == 0 {
+                    ready.insert(blocked);
+                }
+            }
+        }
+
+        if order.len() == self.nodes.len() {
+            Ok(order)
+        } else {
+            let issues = self.cycles().into_iter().next().unwrap_or_default();
+            Err(CycleError { issues })
+        }
+    }
+
+    /// The heaviest chain of blocking issues, e.g. weighted by
+    /// `|issue| issue.map_or(0, |issue| issue.fields.original_estimate.unwrap_or(0).into())`.
+    pub fn critical_path<F>(&self, weight: F) -> Result<CriticalPath, CycleError>
+    where
+        F: Fn(Option<&Issue>) -> u64,
+    {
+        let order = self.topological_order()?;
+        let blocking = self.blocking();
+
+        // Heaviest chain ending at each issue and the issue before it, and
+        // the heaviest chain leading up to it.
+        let mut chains = HashMap::<&str, (u64, Option<&str>)>::new();
+        let mut incoming = HashMap::<&str, (u64, Option<&str>)>::new();
+
+        for key in &order {
+            let (before, previous) = incoming.get(key).copied().unwrap_or((0, None));
+            let total = before + weight(self.issue(key));
+            chains.insert(key, (total, previous));
+
+            for blocked in &blocking[key] {
+                let chain = incoming.entry(blocked).or_insert((0, None));
+                if chain.1.is_none() || total > chain.0 {
+                    *chain = (total, Some(key));
+                }
+            }
+        }
+
+        let end = order
+            .iter()
+            .copied()
+            .max_by(|a, b| chains[a].0.cmp(&chains[b].0).then_with(|| b.cmp(a)));
+
+        let mut issues = vec![];
+        let mut current = end;
+
+        while let Some(key) = current {
+            issues.push(key.to_owned());
+            current = chains[key].1;
+        }
+
+        issues.reverse();
+
+        Ok(CriticalPath {
+            weight: end.map_or(0, |key| chains[key].0),
+            issues,
+        })
+    }
+
+    /// Graphviz source, blocking links are drawn bold and subtasks dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph issues {\n    node [shape=box];\n");
+
+        for (key, issue) in &self.nodes {
+            let label = match issue {
+                Some(issue) => format!("{}\n{}", key, issue.fields.summary),
+                None => key.clone(),
+            };
+
+            let _ = writeln!(dot, "    {} [label={}];", dot_id(key), dot_id(&label));
+        }
+
+        for edge in &self.edges {
+            let attributes = match &edge.kind {
+                EdgeKind::Link(link_type) if edge.is_blocking() => {
+                    format!("label={}, style=bold", dot_id(&link_type.outward))
+                }
+                EdgeKind::Link(link_type) => format!("label={}", dot_id(&link_type.outward)),
+                EdgeKind::Subtask => "style=dashed".to_owned(),
+            };
+
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [{}];",
+                dot_id(&edge.from),
+                dot_id(&edge.to),
+                attributes
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn dot_id(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn issue(key: &str, estimate: u32, links: serde_json::Value) -> Issue {
+        serde_json::from_value(json!({
+            "id": "1",
+            "key": key,
+            "fields": {
+                "summary": format!("Summary of {}", key),
+                "creator": {"key": "chipp", "name": "chipp"},
+                "created": "2019-10-14T15:59:50.000-0400",
+                "issuetype": {"name": "Task"},
+                "status": {"name": "Open"},
+                "timeoriginalestimate": estimate,
+                "issuelinks": links
+            }
+        }))
+        .unwrap()
+    }
+
+    fn blocks(outward: Option<&str>, inward: Option<&str>) -> serde_json::Value {
+        let mut link = json!({
+            "id": "1",
+            "type": {"id": "1", "name": "Blocks", "inward": "is blocked by", "outward": "blocks"}
+        });
+
+        if let Some(key) = outward {
+            link["outwardIssue"] = json!({"id": "1", "key": key});
+        }
+        if let Some(key) = inward {
+            link["inwardIssue"] = json!({"id": "1", "key": key});
+        }
+
+        link
+    }
+
+    fn estimate(issue: Option<&Issue>) -> u64 {
+        issue.map_or(0, |issue| {
+            issue.fields.original_estimate.unwrap_or(0).into()
+        })
+    }
+
+    // RS-1 blocks RS-2 and RS-3, both block RS-4, RS-5 relates to RS-1.
+    fn graph() -> IssueGraph {
+        let mut graph = IssueGraph::new();
+
+        graph.add_issue(issue(
+            "RS-1",
+            1,
+            json!([
+                blocks(Some("RS-2"), None),
+                blocks(Some("RS-3"), None),
+                {
+                    "id": "2",
+                    "type": {"id": "2", "name": "Relates", "inward": "relates to", "outward": "relates to"},
+                    "outwardIssue": {"id": "5", "key": "RS-5"}
+                }
+            ]),
+        ));
+        graph.add_issue(issue(
+            "RS-2",
+            5,
+            json!([blocks(None, Some("RS-1")), blocks(Some("RS-4"), None)]),
+        ));
+        graph.add_issue(issue("RS-3", 2, json!([blocks(Some("RS-4"), None)])));
+        graph.add_issue(issue("RS-4", 3, json!([])));
+
+        graph
+    }
+
+    #[test]
+    fn building() {
+        let graph = graph();
+
+        assert_eq!(
+            graph.keys().collect::<Vec<_>>(),
+            ["RS-1", "RS-2", "RS-3", "RS-4", "RS-5"]
+        );
+        assert_eq!(graph.edges().len(), 5);
+        assert!(graph.issue("RS-5").is_none());
+
+        let mut neighbours = graph.neighbours("RS-2").collect::<Vec<_>>();
+        neighbours.sort();
+        assert_eq!(neighbours, ["RS-1", "RS-4"]);
+    }
+
+    #[test]
+    fn ordering() {
+        let graph = graph();
+
+        assert!(graph.cycles().is_empty());
+        assert_eq!(
+            graph.topological_order().unwrap(),
+            ["RS-1", "RS-2", "RS-3", "RS-4", "RS-5"]
+        );
+        assert_eq!(
+            graph.critical_path(estimate).unwrap(),
+            CriticalPath {
+                issues: vec!["RS-1".to_owned(), "RS-2".to_owned(), "RS-4".to_owned()],
+                weight: 9,
+            }
+        );
+    }
+
+    #[test]
+    fn cycles() {
+        let mut graph = graph();
+        graph.add_issue(issue("RS-4", 3, json!([blocks(Some("RS-1"), None)])));
+
+        assert_eq!(
+            graph.cycles(),
+            vec![vec![
+                "RS-1".to_owned(),
+                "RS-2".to_owned(),
+                "RS-4".to_owned(),
+                "RS-3".to_owned()
+            ]]
+        );
+        assert!(graph.topological_order().is_err());
+        assert!(graph.critical_path(estimate).is_err());
+    }
+
+    #[test]
+    fn dot() {
+        let mut graph = IssueGraph::new();
+        graph.add_issue(issue("RS-1", 1, json!([blocks(Some("RS-2"), None)])));
+
+        assert_eq!(
+            graph.to_dot(),
+            "digraph issues {\n    \
+             node [shape=box];\n    \
+             \"RS-1\" [label=\"RS-1\\nSummary of RS-1\"];\n    \
+             \"RS-2\" [label=\"RS-2\"];\n    \
+             \"RS-1\" -> \"RS-2\" [label=\"blocks\", style=bold];\n\
+             }\n"
+        );
+    }
+}
@@ -1,13 +1,73 @@
-use super::{changelog::Changelog, user::User, worklog::Worklogs};
+use super::{
+    attachment::Attachment,
+    changelog::Changelog,
+    error::ErrorCollection,
+    field::{CustomField, FieldRegistry},
+    sprint::Sprint,
+    transition::Transition,
+    user::User,
+    worklog::Worklogs,
+};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, DeserializeOwned},
+    Deserialize, Deserializer, Serialize,
+};
+use std::collections::HashMap;
+use std::ops::BitOr;
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct Issue {
     pub id: String,
     pub key: String,
     pub fields: Fields,
+
+    /// Requires `Expand::CHANGELOG`.
     pub changelog: Option<Changelog>,
+    /// Field values rendered as HTML, requires `Expand::RENDERED_FIELDS`.
+    pub rendered_fields: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Display names by field id, requires `Expand::NAMES`.
+    pub names: Option<HashMap<String, String>>,
+    /// Field schemas by field id, requires `Expand::SCHEMA`.
+    pub schema: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Requires `Expand::TRANSITIONS`.
+    pub transitions: Option<Vec<Transition>>,
+}
+
+impl Issue {
+    /// Reads a field not modelled in `Fields` by its id, e.g. `customfield_12345`.
+    ///
+    /// Returns `Ok(None)` when the field is missing or `null`.
+    pub fn custom<T: DeserializeOwned>(&self, id: &str) -> Result<Option<T>, serde_json::Error> {
+        match self.fields.other.get(id) {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(value) => T::deserialize(value).map(Some),
+        }
+    }
+
+    /// Reads a field by its display name, resolved through the field metadata.
+    pub fn custom_by_name<T: DeserializeOwned>(
+        &self,
+        registry: &FieldRegistry,
+        name: &str,
+    ) -> Result<Option<T>, serde_json::Error> {
+        match registry.id_by_name(name) {
+            Some(id) => self.custom(id),
+            None => Ok(None),
+        }
+    }
+
+    pub fn custom_field<T: DeserializeOwned>(
+        &self,
+        registry: &FieldRegistry,
+        field: CustomField,
+    ) -> Result<Option<T>, serde_json::Error> {
+        match registry.id(field) {
+            Some(id) => self.custom(id),
+            None => Ok(None),
+        }
+    }
 }
 
 use std::fmt;
@@ -26,9 +86,191 @@ pub const MANDATORY_ISSUE_FIELDS: &[&str] = &[
     "summary",
 ];
 
+// Ids of the fields modelled in `Fields`, indexed by the bits of `FieldSet::known`.
+const KNOWN_FIELDS: &[&str] = &[
+    "summary",
+    "description",
+    "creator",
+    "created",
+    "resolutiondate",
+    "assignee",
+    "worklog",
+    "issuetype",
+    "status",
+    "priority",
+    "parent",
+    "subtasks",
+    "issuelinks",
+    "timeoriginalestimate",
+    "timespent",
+    "aggregatetimespent",
+    "labels",
+    "attachment",
+];
+
+// Logical custom fields follow the standard ones, in `CustomField::ALL` order.
+const CUSTOM_FIELDS_OFFSET: u32 = 24;
+
+/// Issue fields to request. `MANDATORY_ISSUE_FIELDS` are always requested
+/// in addition, so the result always deserializes into `Fields`.
+///
+/// ```
+/// use jira_api::issue::FieldSet;
+///
+/// let fields = FieldSet::ASSIGNEE | FieldSet::WORKLOG | FieldSet::STORY_POINTS;
+/// let fields = fields.with_id("customfield_12345");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldSet {
+    known: u32,
+    other: Vec<String>,
+}
+
+impl FieldSet {
+    pub const NONE: FieldSet = FieldSet::known(0);
+    pub const SUMMARY: FieldSet = FieldSet::standard(0);
+    pub const DESCRIPTION: FieldSet = FieldSet::standard(1);
+    pub const CREATOR: FieldSet = FieldSet::standard(2);
+    pub const CREATED: FieldSet = FieldSet::standard(3);
+    pub const RESOLUTION_DATE: FieldSet = FieldSet::standard(4);
+    pub const ASSIGNEE: FieldSet = FieldSet::standard(5);
+    pub const WORKLOG: FieldSet = FieldSet::standard(6);
+    pub const ISSUE_TYPE: FieldSet = FieldSet::standard(7);
+    pub const STATUS: FieldSet = FieldSet::standard(8);
+    pub const PRIORITY: FieldSet = FieldSet::standard(9);
+    pub const PARENT: FieldSet = FieldSet::standard(10);
+    pub const SUBTASKS: FieldSet = FieldSet::standard(11);
+    pub const ISSUE_LINKS: FieldSet = FieldSet::standard(12);
+    pub const ORIGINAL_ESTIMATE: FieldSet = FieldSet::standard(13);
+    pub const TIME_SPENT: FieldSet = FieldSet::standard(14);
+    pub const TOTAL_TIME_SPENT: FieldSet = FieldSet::standard(15);
+    pub const LABELS: FieldSet = FieldSet::standard(16);
+    pub const ATTACHMENTS: FieldSet = FieldSet::standard(17);
+    /// Resolved through the client's `FieldRegistry`.
+    pub const STORY_POINTS: FieldSet = FieldSet::custom(CustomField::StoryPoints);
+    /// Resolved through the client's `FieldRegistry`.
+    pub const SPRINTS: FieldSet = FieldSet::custom(CustomField::Sprint);
+
+    const fn known(bits: u32) -> FieldSet {
+        FieldSet {
+            known: bits,
+            other: Vec::new(),
+        }
+    }
+
+    const fn standard(index: u32) -> FieldSet {
+        FieldSet::known(1 << index)
+    }
+
+    /// A logical custom field, resolved through the client's `FieldRegistry`.
+    pub const fn custom(field: CustomField) -> FieldSet {
+        let index = match field {
+            CustomField::StoryPoints => 0,
+            CustomField::Sprint => 1,
+            CustomField::EpicLink => 2,
+            CustomField::Team => 3,
+        };
+
+        FieldSet::known(1 << (CUSTOM_FIELDS_OFFSET + index))
+    }
+
+    /// Adds a field not modelled in `Fields` by its id, read with `Issue::custom`.
+    pub fn with_id<I: Into<String>>(mut self, id: I) -> FieldSet {
+        let id = id.into();
+        if !self.other.contains(&id) {
+            self.other.push(id);
+        }
+        self
+    }
+
+    pub fn contains(&self, fields: &FieldSet) -> bool {
+        self.known & fields.known == fields.known
+            && fields.other.iter().all(|id| self.other.contains(id))
+    }
+
+    /// Field ids to request, custom fields the registry doesn't know are skipped.
+    pub fn ids<'a>(&'a self, registry: &'a FieldRegistry) -> impl Iterator<Item = &'a str> {
+        let standard = KNOWN_FIELDS
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.known & (1 << index) != 0)
+            .map(|(_, id)| *id);
+
+        let custom = CustomField::ALL
+            .into_iter()
+            .filter(|field| self.contains(&FieldSet::custom(*field)))
+            .filter_map(|field| registry.id(field));
+
+        standard
+            .chain(custom)
+            .chain(self.other.iter().map(String::as_str))
+    }
+}
+
+impl BitOr for FieldSet {
+    type Output = FieldSet;
+
+    fn bitor(mut self, rhs: FieldSet) -> FieldSet {
+        self.known |= rhs.known;
+        rhs.other.into_iter().fold(self, FieldSet::with_id)
+    }
+}
+
+/// Optional parts of an issue response.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Expand(u8);
+
+impl Expand {
+    pub const NONE: Expand = Expand(0);
+    /// Fills `Issue::changelog`.
+    pub const CHANGELOG: Expand = Expand(1);
+    /// Fills `Issue::rendered_fields`.
+    pub const RENDERED_FIELDS: Expand = Expand(1 << 1);
+    /// Fills `Issue::names`.
+    pub const NAMES: Expand = Expand(1 << 2);
+    /// Fills `Issue::schema`.
+    pub const SCHEMA: Expand = Expand(1 << 3);
+    /// Fills `Issue::transitions`.
+    pub const TRANSITIONS: Expand = Expand(1 << 4);
+
+    const NAMED: [(Expand, &'static str); 5] = [
+        (Expand::CHANGELOG, "changelog"),
+        (Expand::RENDERED_FIELDS, "renderedFields"),
+        (Expand::NAMES, "names"),
+        (Expand::SCHEMA, "schema"),
+        (Expand::TRANSITIONS, "transitions"),
+    ];
+
+    pub fn contains(self, expand: Expand) -> bool {
+        self.0 & expand.0 == expand.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The values of the `expand` request parameter.
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        Expand::NAMED
+            .into_iter()
+            .filter(move |(expand, _)| self.contains(*expand))
+            .map(|(_, name)| name)
+    }
+}
+
+impl BitOr for Expand {
+    type Output = Expand;
+
+    fn bitor(self, rhs: Expand) -> Expand {
+        Expand(self.0 | rhs.0)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(remote = "Self")]
 pub struct Fields {
     pub summary: String,
+    #[serde(deserialize_with = "crate::adf::deserialize_optional_text", default)]
     pub description: Option<String>,
 
     pub creator: User,
@@ -42,10 +284,14 @@ pub struct Fields {
 
     pub assignee: Option<User>,
 
-    #[serde(rename = "customfield_10182")]
+    /// Resolved through the client's `FieldRegistry`, like `sprints`. JSON
+    /// that didn't come through the client is read with the legacy ids
+    /// `customfield_10182` and `customfield_10231`.
+    #[serde(rename = "$storyPoints")]
     pub story_points: Option<f32>,
-    #[serde(rename = "customfield_10231")]
-    pub sprints: Option<Vec<String>>,
+    #[serde(rename = "$sprint", default)]
+    #[serde(deserialize_with = "crate::sprint::deserialize_optional_sprints")]
+    pub sprints: Option<Vec<Sprint>>,
 
     #[serde(rename = "worklog")]
     pub work_logs: Option<Worklogs>,
@@ -72,6 +318,22 @@ pub struct Fields {
 
     #[serde(default)]
     pub labels: Vec<String>,
+
+    #[serde(rename = "attachment")]
+    pub attachments: Option<Vec<Attachment>>,
+
+    /// Every field not modelled above, keyed by field id.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<'de> Deserialize<'de> for Fields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Fields, D::Error> {
+        let mut fields = serde_json::Map::deserialize(deserializer)?;
+        FieldRegistry::canonicalize_legacy(&mut fields);
+
+        Fields::deserialize(serde_json::Value::Object(fields)).map_err(de::Error::custom)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, PartialEq)]
@@ -80,9 +342,10 @@ pub struct ModifyFields {
     pub summary: Option<String>,
     pub description: Option<String>,
 
-    #[serde(rename = "customfield_10182", skip_serializing_if = "Option::is_none")]
+    /// Sent to the id the client's `FieldRegistry` resolves, like `sprints`.
+    #[serde(rename = "$storyPoints", skip_serializing_if = "Option::is_none")]
     pub story_points: Option<f32>,
-    #[serde(rename = "customfield_10231", skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "$sprint", skip_serializing_if = "Option::is_none")]
     pub sprints: Option<Vec<String>>,
 
     #[serde(rename = "issuetype", skip_serializing_if = "Option::is_none")]
@@ -127,6 +390,111 @@ impl ModifyFields {
     }
 }
 
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct CreateFields {
+    pub project: KeyRef,
+    #[serde(rename = "issuetype")]
+    pub issue_type: IssueType,
+    pub summary: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<KeyRef>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Sent to the id the client's `FieldRegistry` resolves.
+    #[serde(rename = "$storyPoints", skip_serializing_if = "Option::is_none")]
+    pub story_points: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<IssuePriority>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+
+    #[serde(flatten)]
+    pub custom_fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CreateFields {
+    pub fn new<P, T, S>(project_key: P, issue_type: T, summary: S) -> CreateFields
+    where
+        P: ToString,
+        T: ToString,
+        S: ToString,
+    {
+        CreateFields {
+            project: KeyRef::new(project_key),
+            issue_type: IssueType {
+                name: issue_type.to_string(),
+            },
+            summary: summary.to_string(),
+            parent: None,
+            description: None,
+            story_points: None,
+            priority: None,
+            labels: None,
+            custom_fields: serde_json::Map::new(),
+        }
+    }
+
+    pub fn subtask<P, T, K, S>(
+        project_key: P,
+        issue_type: T,
+        parent_key: K,
+        summary: S,
+    ) -> CreateFields
+    where
+        P: ToString,
+        T: ToString,
+        K: ToString,
+        S: ToString,
+    {
+        let mut fields = CreateFields::new(project_key, issue_type, summary);
+        fields.parent = Some(KeyRef::new(parent_key));
+        fields
+    }
+
+    pub fn set_custom_field<K, V>(&mut self, id: K, value: V)
+    where
+        K: ToString,
+        V: Into<serde_json::Value>,
+    {
+        self.custom_fields.insert(id.to_string(), value.into());
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeyRef {
+    pub key: String,
+}
+
+impl KeyRef {
+    pub fn new<K: ToString>(key: K) -> KeyRef {
+        KeyRef {
+            key: key.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct BulkCreateResult {
+    #[serde(default)]
+    pub issues: Vec<ShortIssue>,
+    #[serde(default)]
+    pub errors: Vec<BulkCreateError>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkCreateError {
+    pub status: u16,
+    pub element_errors: ErrorCollection,
+    /// Index of the failed item in the submitted batch.
+    pub failed_element_number: usize,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct IssueType {
     pub name: String,
@@ -158,7 +526,301 @@ pub struct IssueLink {
     pub outward_issue: Option<ShortIssue>,
 }
 
+/// How two issues relate, e.g. `Blocks` with the outward description
+/// `blocks` and the inward one `is blocked by`.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct IssueLinkType {
     pub id: String,
+    pub name: String,
+    pub inward: String,
+    pub outward: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IssueLinkTypes {
+    pub(crate) issue_link_types: Vec<IssueLinkType>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn custom_fields() {
+        let mut json = json!({
+            "id": "10010",
+            "key": "RS-1",
+            "fields": {
+                "summary": "Custom fields",
+                "creator": {"key": "chipp", "name": "chipp"},
+                "created": "2019-10-14T15:59:50.000-0400",
+                "issuetype": {"name": "Task"},
+                "status": {"name": "Open"},
+                "customfield_10182": 3.0,
+                "customfield_12345": {"value": "Core", "id": "1"},
+                "customfield_12346": null,
+                "customfield_12347": "Platform"
+            }
+        });
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct SelectOption {
+            value: String,
+        }
+
+        FieldRegistry::default().canonicalize_issues(&mut json);
+        let issue: Issue = serde_json::from_value(json).unwrap();
+
+        assert_eq!(issue.fields.story_points, Some(3.0));
+        assert_eq!(issue.custom::<f32>("customfield_10182").unwrap(), Some(3.0));
+        assert_eq!(
+            issue.custom::<SelectOption>("customfield_12345").unwrap(),
+            Some(SelectOption {
+                value: "Core".to_owned()
+            })
+        );
+        assert_eq!(issue.custom::<String>("customfield_12346").unwrap(), None);
+        assert_eq!(issue.custom::<String>("customfield_99999").unwrap(), None);
+        assert!(issue.custom::<u32>("customfield_12345").is_err());
+
+        let fields = serde_json::from_value(json!([
+            {"id": "customfield_12347", "name": "Team", "custom": true}
+        ]))
+        .unwrap();
+        let registry = FieldRegistry::from_fields(fields);
+
+        assert_eq!(
+            issue.custom_by_name::<String>(&registry, "team").unwrap(),
+            Some("Platform".to_owned())
+        );
+        assert_eq!(
+            issue
+                .custom_field::<String>(&registry, CustomField::Team)
+                .unwrap(),
+            Some("Platform".to_owned())
+        );
+    }
+
+    #[test]
+    fn raw_issue_uses_legacy_ids() {
+        let json = json!({
+            "id": "10012",
+            "key": "RS-3",
+            "fields": {
+                "summary": "From a webhook",
+                "creator": {"key": "chipp", "name": "chipp"},
+                "created": "2019-10-14T15:59:50.000-0400",
+                "issuetype": {"name": "Task"},
+                "status": {"name": "Open"},
+                "customfield_10182": 2.0,
+                "customfield_10231": [
+                    "com.atlassian.greenhopper.service.sprint.Sprint@1[id=1001,rapidViewId=123,state=ACTIVE,name=Sprint 1,startDate=2020-02-18T11:36:36.825Z,endDate=2020-03-02T22:01:00.000Z,completeDate=<null>,sequence=1001,goal=]"
+                ]
+            }
+        });
+
+        let issue: Issue = serde_json::from_value(json).unwrap();
+
+        assert_eq!(issue.fields.story_points, Some(2.0));
+        assert_eq!(issue.custom::<f32>("customfield_10182").unwrap(), Some(2.0));
+        assert_eq!(issue.fields.sprints.unwrap()[0].id, 1001);
+    }
+
+    #[test]
+    fn custom_field_under_legacy_id() {
+        let mut json = json!({
+            "id": "10011",
+            "key": "RS-2",
+            "fields": {
+                "summary": "Remapped story points",
+                "creator": {"key": "chipp", "name": "chipp"},
+                "created": "2019-10-14T15:59:50.000-0400",
+                "issuetype": {"name": "Task"},
+                "status": {"name": "Open"},
+                "customfield_10002": 5.0,
+                "customfield_10182": "Platform"
+            }
+        });
+
+        let mut registry = FieldRegistry::default();
+        registry.set_id(CustomField::StoryPoints, "customfield_10002");
+        registry.canonicalize_issues(&mut json);
+
+        let issue: Issue = serde_json::from_value(json).unwrap();
+
+        assert_eq!(issue.fields.story_points, Some(5.0));
+        assert_eq!(
+            issue.custom::<String>("customfield_10182").unwrap(),
+            Some("Platform".to_owned())
+        );
+        assert_eq!(
+            issue
+                .custom_field::<f32>(&registry, CustomField::StoryPoints)
+                .unwrap(),
+            Some(5.0)
+        );
+    }
+
+    #[test]
+    fn create_fields_serialization() {
+        let mut fields = CreateFields::subtask("RS", "Sub-task", "RS-1", "Write tests");
+        fields.story_points = Some(2.0);
+        fields.set_custom_field("customfield_12345", json!({"value": "Core"}));
+
+        assert_eq!(
+            serde_json::to_value(&fields).unwrap(),
+            json!({
+                "project": {"key": "RS"},
+                "issuetype": {"name": "Sub-task"},
+                "summary": "Write tests",
+                "parent": {"key": "RS-1"},
+                "$storyPoints": 2.0,
+                "customfield_12345": {"value": "Core"}
+            })
+        );
+    }
+
+    #[test]
+    fn bulk_create_result_parsing() {
+        let json = json!({
+            "issues": [
+                {"id": "10000", "key": "RS-24", "self": "https://jira.example.io/rest/api/2/issue/10000"}
+            ],
+            "errors": [
+                {
+                    "status": 400,
+                    "elementErrors": {
+                        "errorMessages": [],
+                        "errors": {"issuetype": "valid issue type is required"}
+                    },
+                    "failedElementNumber": 1
+                }
+            ]
+        });
+
+        let result: BulkCreateResult = serde_json::from_value(json).unwrap();
+
+        assert_eq!(result.issues.len(), 1);
+        assert_eq!(result.issues[0].key, "RS-24");
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].failed_element_number, 1);
+        assert_eq!(
+            result.errors[0].element_errors.errors.get("issuetype"),
+            Some(&"valid issue type is required".to_owned())
+        );
+    }
+
+    #[test]
+    fn field_set() {
+        let mut registry = FieldRegistry::default();
+        registry.set_id(CustomField::StoryPoints, "customfield_10002");
+
+        let fields = (FieldSet::WORKLOG | FieldSet::LABELS | FieldSet::STORY_POINTS)
+            .with_id("customfield_12345")
+            | FieldSet::NONE.with_id("customfield_12345");
+
+        assert_eq!(
+            fields.ids(&registry).collect::<Vec<_>>(),
+            [
+                "worklog",
+                "labels",
+                "customfield_10002",
+                "customfield_12345"
+            ]
+        );
+        assert!(fields.contains(&(FieldSet::LABELS | FieldSet::STORY_POINTS)));
+        assert!(!fields.contains(&FieldSet::SPRINTS));
+
+        // Unknown to the registry, nothing to request.
+        assert_eq!(
+            FieldSet::custom(CustomField::Team).ids(&registry).count(),
+            0
+        );
+
+        let expand = Expand::TRANSITIONS | Expand::CHANGELOG;
+        assert_eq!(
+            expand.names().collect::<Vec<_>>(),
+            ["changelog", "transitions"]
+        );
+        assert!(Expand::NONE.is_empty());
+    }
+
+    #[test]
+    fn expanded_issue() {
+        let json = json!({
+            "id": "10010",
+            "key": "RS-1",
+            "fields": {
+                "summary": "Expanded",
+                "creator": {"key": "chipp", "name": "chipp"},
+                "created": "2019-10-14T15:59:50.000-0400",
+                "issuetype": {"name": "Task"},
+                "status": {"name": "Open"}
+            },
+            "changelog": {
+                "startAt": 0,
+                "maxResults": 1,
+                "total": 1,
+                "histories": [{
+                    "id": "20001",
+                    "author": {"key": "chipp", "name": "chipp"},
+                    "created": "2019-10-15T10:00:00.000-0400",
+                    "items": [{
+                        "field": "status",
+                        "from": "1",
+                        "fromString": "Open",
+                        "to": "3",
+                        "toString": "In Progress"
+                    }]
+                }]
+            },
+            "renderedFields": {"description": "<p>Expanded</p>"},
+            "names": {"summary": "Summary"},
+            "transitions": [{"id": "21", "name": "Start", "to": {"name": "In Progress"}}]
+        });
+
+        let issue: Issue = serde_json::from_value(json).unwrap();
+
+        let changelog = issue.changelog.unwrap();
+        assert_eq!(changelog.histories[0].id, "20001");
+        assert_eq!(
+            changelog.histories[0].items[0].to_string.as_deref(),
+            Some("In Progress")
+        );
+        assert_eq!(
+            issue.rendered_fields.unwrap()["description"],
+            "<p>Expanded</p>"
+        );
+        assert_eq!(issue.names.unwrap()["summary"], "Summary");
+        assert_eq!(issue.transitions.unwrap()[0].name, "Start");
+        assert!(issue.schema.is_none());
+    }
+
+    #[test]
+    fn issue_link_types() {
+        let types: IssueLinkTypes = serde_json::from_value(json!({
+            "issueLinkTypes": [{
+                "id": "10000",
+                "name": "Blocks",
+                "inward": "is blocked by",
+                "outward": "blocks",
+                "self": "https://jira.example.io/rest/api/2/issueLinkType/10000"
+            }]
+        }))
+        .unwrap();
+
+        let link: IssueLink = serde_json::from_value(json!({
+            "id": "20001",
+            "type": types.issue_link_types[0],
+            "outwardIssue": {"id": "10011", "key": "RS-2"}
+        }))
+        .unwrap();
+
+        assert_eq!(link.r#type.name, "Blocks");
+        assert_eq!(link.r#type.outward, "blocks");
+        assert_eq!(link.outward_issue.unwrap().key, "RS-2");
+        assert!(link.inward_issue.is_none());
+    }
 }
@@ -0,0 +1,789 @@
+//! Typed JQL queries that render with properly quoted fields and values.
+//!
+//! ```
+//! use jira_api::jql::{self, field, Direction, Jql};
+//!
+//! let query = Jql::new(
+//!     field("project").eq("RS")
+//!         .and(field("assignee").eq(jql::current_user()))
+//!         .and(field("sprint").is_in([jql::open_sprints()]))
+//!         .and(field("summary").contains(r#"say "hello""#)),
+//! )
+//! .order_by("created", Direction::Desc);
+//!
+//! assert_eq!(
+//!     query.to_string(),
+//!     r#"project = "RS" AND assignee = currentUser() AND sprint IN (openSprints()) AND summary ~ "say \"hello\"" ORDER BY created DESC"#
+//! );
+//! ```
+//!
+//! Existing queries can be parsed into the same tree, e.g. to scope a saved
+//! filter to a single project:
+//!
+//! ```
+//! use jira_api::jql::{field, Jql};
+//!
+//! let query: Jql = "status = Done OR resolution IS NOT EMPTY ORDER BY updated".parse().unwrap();
+//! let query = query.and(field("project").eq("RS"));
+//!
+//! assert_eq!(
+//!     query.to_string(),
+//!     r#"(status = Done OR resolution IS NOT EMPTY) AND project = "RS" ORDER BY updated"#
+//! );
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+mod parser;
+
+pub use parser::{parse, ParseError};
+
+/// A complete query: an optional condition and the sort order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Jql {
+    pub clause: Option<Clause>,
+    pub order_by: Vec<OrderBy>,
+}
+
+impl Jql {
+    pub fn new(clause: Clause) -> Jql {
+        Jql {
+            clause: Some(clause),
+            order_by: vec![],
+        }
+    }
+
+    /// Matches every issue visible to the user.
+    pub fn all() -> Jql {
+        Jql::default()
+    }
+
+    pub fn order_by<F: Into<String>>(mut self, field: F, direction: Direction) -> Jql {
+        self.order_by.push(OrderBy {
+            field: field.into(),
+            direction: Some(direction),
+        });
+        self
+    }
+
+    /// Narrows the query down, keeping the sort order.
+    pub fn and(mut self, clause: Clause) -> Jql {
+        self.clause = Some(match self.clause {
+            Some(existing) => existing.and(clause),
+            None => clause,
+        });
+        self
+    }
+
+    /// Every field the query filters or sorts by, in order of appearance.
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = vec![];
+
+        if let Some(clause) = &self.clause {
+            clause.collect_fields(&mut fields);
+        }
+
+        fields.extend(self.order_by.iter().map(|order| order.field.as_str()));
+        fields
+    }
+}
+
+impl FromStr for Jql {
+    type Err = ParseError;
+
+    fn from_str(query: &str) -> Result<Jql, ParseError> {
+        parse(query)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Clause {
+    And(Vec<Clause>),
+    Or(Vec<Clause>),
+    Not(Box<Clause>),
+    Compare {
+        field: String,
+        operator: Operator,
+        operand: Operand,
+    },
+}
+
+impl Clause {
+    pub fn and(self, other: Clause) -> Clause {
+        match self {
+            Clause::And(mut clauses) => {
+                clauses.push(other);
+                Clause::And(clauses)
+            }
+            clause => Clause::And(vec![clause, other]),
+        }
+    }
+
+    pub fn or(self, other: Clause) -> Clause {
+        match self {
+            Clause::Or(mut clauses) => {
+                clauses.push(other);
+                Clause::Or(clauses)
+            }
+            clause => Clause::Or(vec![clause, other]),
+        }
+    }
+
+    fn collect_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            Clause::And(clauses) | Clause::Or(clauses) => {
+                for clause in clauses {
+                    clause.collect_fields(fields);
+                }
+            }
+            Clause::Not(clause) => clause.collect_fields(fields),
+            Clause::Compare { field, .. } => fields.push(field),
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Clause::Or(_) => 0,
+            Clause::And(_) => 1,
+            Clause::Not(_) => 2,
+            Clause::Compare { .. } => 3,
+        }
+    }
+}
+
+impl std::ops::Not for Clause {
+    type Output = Clause;
+
+    fn not(self) -> Clause {
+        Clause::Not(Box::new(self))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    NotEq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    /// `~`, text search.
+    Contains,
+    /// `!~`
+    NotContains,
+    In,
+    NotIn,
+    Is,
+    IsNot,
+    Was,
+    WasNot,
+    WasIn,
+    WasNotIn,
+}
+
+impl Operator {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::NotEq => "!=",
+            Operator::Gt => ">",
+            Operator::Gte => ">=",
+            Operator::Lt => "<",
+            Operator::Lte => "<=",
+            Operator::Contains => "~",
+            Operator::NotContains => "!~",
+            Operator::In => "IN",
+            Operator::NotIn => "NOT IN",
+            Operator::Is => "IS",
+            Operator::IsNot => "IS NOT",
+            Operator::Was => "WAS",
+            Operator::WasNot => "WAS NOT",
+            Operator::WasIn => "WAS IN",
+            Operator::WasNotIn => "WAS NOT IN",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    /// Always rendered as a quoted string.
+    Value(String),
+    /// Rendered unquoted, kept as text so large ids and decimals like
+    /// `1.10` stay exactly as given.
+    Number(String),
+    /// An unquoted value of a parsed query, rendered as it was written.
+    Word(String),
+    Function {
+        name: String,
+        args: Vec<String>,
+    },
+    List(Vec<Operand>),
+    /// `EMPTY`, used with `IS` and `IS NOT`.
+    Empty,
+}
+
+impl From<&str> for Operand {
+    fn from(value: &str) -> Operand {
+        Operand::Value(value.to_owned())
+    }
+}
+
+impl From<String> for Operand {
+    fn from(value: String) -> Operand {
+        Operand::Value(value)
+    }
+}
+
+impl From<&String> for Operand {
+    fn from(value: &String) -> Operand {
+        Operand::Value(value.clone())
+    }
+}
+
+macro_rules! number_operand {
+    ($($type:ty),*) => {
+        $(
+            impl From<$type> for Operand {
+                fn from(value: $type) -> Operand {
+                    Operand::Number(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+number_operand!(i32, i64, u32, u64);
+
+/// NaN and infinity have no JQL literal, so floats convert with `TryFrom`,
+/// e.g. `field("ratio").gt(Operand::try_from(0.1)?)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonFiniteNumber;
+
+impl fmt::Display for NonFiniteNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JQL numbers must be finite")
+    }
+}
+
+impl std::error::Error for NonFiniteNumber {}
+
+macro_rules! float_operand {
+    ($($type:ty),*) => {
+        $(
+            impl TryFrom<$type> for Operand {
+                type Error = NonFiniteNumber;
+
+                fn try_from(value: $type) -> Result<Operand, NonFiniteNumber> {
+                    if value.is_finite() {
+                        Ok(Operand::Number(value.to_string()))
+                    } else {
+                        Err(NonFiniteNumber)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+float_operand!(f32, f64);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderBy {
+    pub field: String,
+    pub direction: Option<Direction>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+/// Starts a comparison, e.g. `field("status").eq("Done")`.
+pub fn field<F: Into<String>>(name: F) -> Field {
+    Field { name: name.into() }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    name: String,
+}
+
+macro_rules! comparisons {
+    ($($(#[$doc:meta])* $method:ident => $operator:ident),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            pub fn $method<O: Into<Operand>>(self, operand: O) -> Clause {
+                self.compare(Operator::$operator, operand.into())
+            }
+        )*
+    };
+}
+
+impl Field {
+    comparisons! {
+        eq => Eq,
+        not_eq => NotEq,
+        gt => Gt,
+        gte => Gte,
+        lt => Lt,
+        lte => Lte,
+        /// Text search, `~`.
+        contains => Contains,
+        not_contains => NotContains,
+        was => Was,
+        was_not => WasNot,
+    }
+
+    pub fn is_in<I, O>(self, values: I) -> Clause
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<Operand>,
+    {
+        self.compare(Operator::In, list(values))
+    }
+
+    pub fn not_in<I, O>(self, values: I) -> Clause
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<Operand>,
+    {
+        self.compare(Operator::NotIn, list(values))
+    }
+
+    pub fn was_in<I, O>(self, values: I) -> Clause
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<Operand>,
+    {
+        self.compare(Operator::WasIn, list(values))
+    }
+
+    pub fn is_empty(self) -> Clause {
+        self.compare(Operator::Is, Operand::Empty)
+    }
+
+    pub fn is_not_empty(self) -> Clause {
+        self.compare(Operator::IsNot, Operand::Empty)
+    }
+
+    pub fn compare(self, operator: Operator, operand: Operand) -> Clause {
+        Clause::Compare {
+            field: self.name,
+            operator,
+            operand,
+        }
+    }
+}
+
+fn list<I, O>(values: I) -> Operand
+where
+    I: IntoIterator<Item = O>,
+    O: Into<Operand>,
+{
+    Operand::List(values.into_iter().map(Into::into).collect())
+}
+
+pub fn function<N, I, A>(name: N, args: I) -> Operand
+where
+    N: Into<String>,
+    I: IntoIterator<Item = A>,
+    A: Into<String>,
+{
+    Operand::Function {
+        name: name.into(),
+        args: args.into_iter().map(Into::into).collect(),
+    }
+}
+
+pub fn current_user() -> Operand {
+    function("currentUser", Vec::<String>::new())
+}
+
+pub fn open_sprints() -> Operand {
+    function("openSprints", Vec::<String>::new())
+}
+
+pub fn closed_sprints() -> Operand {
+    function("closedSprints", Vec::<String>::new())
+}
+
+pub fn future_sprints() -> Operand {
+    function("futureSprints", Vec::<String>::new())
+}
+
+pub fn members_of<G: Into<String>>(group: G) -> Operand {
+    function("membersOf", [group])
+}
+
+pub fn now() -> Operand {
+    function("now", Vec::<String>::new())
+}
+
+/// `startOfDay()`, or relative to today with an offset such as `-1d`.
+pub fn start_of_day(offset: Option<&str>) -> Operand {
+    function("startOfDay", offset)
+}
+
+pub fn end_of_day(offset: Option<&str>) -> Operand {
+    function("endOfDay", offset)
+}
+
+/// How strictly Jira validates queries passed to `Client::parse_jql`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Validation {
+    /// Unknown fields, values and functions are errors.
+    Strict,
+    /// Unknown references are reported as errors but the structure is still returned.
+    Warn,
+    /// Only the syntax is checked.
+    None,
+}
+
+impl Validation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Validation::Strict => "strict",
+            Validation::Warn => "warn",
+            Validation::None => "none",
+        }
+    }
+}
+
+/// A query as Jira's own parser sees it.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ParsedQuery {
+    pub query: String,
+    /// Jira's syntax tree, missing when the query doesn't parse.
+    pub structure: Option<serde_json::Value>,
+    #[serde(default)]
+    pub errors: Vec<QueryError>,
+}
+
+impl ParsedQuery {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ParsedQueries {
+    pub(crate) queries: Vec<ParsedQuery>,
+}
+
+/// An error reported by Jira, with the location when the message has one,
+/// e.g. `Error in the JQL Query: ... (line 1, character 12)`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+pub struct QueryError {
+    pub message: String,
+    pub line: Option<u32>,
+    pub character: Option<u32>,
+}
+
+impl From<String> for QueryError {
+    fn from(message: String) -> QueryError {
+        let number_after = |label: &str| {
+            let start = message.rfind(label)? + label.len();
+            let digits = message[start..]
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>();
+
+            digits.parse().ok()
+        };
+
+        QueryError {
+            line: number_after("line "),
+            character: number_after("character "),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+// Jira's reserved words plus the JQL keywords missing from that list.
+const RESERVED_WORDS: &str =
+    "a abort access add after alias all alter an and any as asc audit avg before begin \
+     between boolean break by byte catch cf changed char character check checkpoint collate \
+     collation column commit connect continue count create current date decimal declare \
+     decrement default defaults define delete delimiter desc difference distinct divide do \
+     double drop during else empty encoding end equals escape exclusive exec execute exists \
+     explain false fetch file field first float for from function go goto grant greater group \
+     having identified if immediate in increment index initial inner inout input insert int \
+     integer intersect intersection into is isempty isnull join last left less like limit \
+     lock long max min minus mode modify modulo more multiply next noaudit not notin nowait \
+     null number object of on option or order outer output power previous prior privileges \
+     public raise raw remainder rename resource return returns revoke right row rowid rownum \
+     rows select session set share size sqrt start strict string subtract sum synonym table \
+     then to trans transaction trigger true uid union unique update user validate values view \
+     was when whenever where while with";
+
+/// Renders `value` as a quoted JQL string literal.
+pub fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    for char in value.chars() {
+        match char {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            char => quoted.push(char),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn is_plain_field(name: &str) -> bool {
+    let custom_field = name
+        .strip_prefix("cf[")
+        .and_then(|rest| rest.strip_suffix(']'))
+        .is_some_and(|id| !id.is_empty() && id.chars().all(|char| char.is_ascii_digit()));
+
+    let identifier = name
+        .chars()
+        .next()
+        .is_some_and(|char| char.is_ascii_alphabetic() || char == '_')
+        && name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '.');
+
+    custom_field
+        || (identifier
+            && !RESERVED_WORDS
+                .split_whitespace()
+                .any(|word| word.eq_ignore_ascii_case(name)))
+}
+
+struct FieldName<'a>(&'a str);
+
+impl fmt::Display for FieldName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if is_plain_field(self.0) {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "{}", quote(self.0))
+        }
+    }
+}
+
+impl fmt::Display for Jql {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(clause) = &self.clause {
+            write!(f, "{}", clause)?;
+        }
+
+        if !self.order_by.is_empty() {
+            if self.clause.is_some() {
+                write!(f, " ")?;
+            }
+
+            write!(f, "ORDER BY ")?;
+
+            for (index, order) in self.order_by.iter().enumerate() {
+                if index > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", order)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for OrderBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", FieldName(&self.field))?;
+
+        match self.direction {
+            Some(Direction::Asc) => write!(f, " ASC"),
+            Some(Direction::Desc) => write!(f, " DESC"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nested = |f: &mut fmt::Formatter<'_>, clause: &Clause| {
+            if clause.precedence() <= self.precedence() && clause.precedence() < 2 {
+                write!(f, "({})", clause)
+            } else {
+                write!(f, "{}", clause)
+            }
+        };
+
+        match self {
+            Clause::And(clauses) | Clause::Or(clauses) => {
+                let separator = if matches!(self, Clause::And(_)) {
+                    " AND "
+                } else {
+                    " OR "
+                };
+
+                for (index, clause) in clauses.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, "{}", separator)?;
+                    }
+                    nested(f, clause)?;
+                }
+
+                Ok(())
+            }
+            Clause::Not(clause) => {
+                write!(f, "NOT ")?;
+
+                match clause.as_ref() {
+                    Clause::Compare { .. } | Clause::Not(_) => write!(f, "{}", clause),
+                    clause => write!(f, "({})", clause),
+                }
+            }
+            Clause::Compare {
+                field,
+                operator,
+                operand,
+            } => write!(f, "{} {} {}", FieldName(field), operator.as_str(), operand),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Value(value) => write!(f, "{}", quote(value)),
+            Operand::Number(number) => write!(f, "{}", number),
+            Operand::Word(word) => write!(f, "{}", word),
+            Operand::Function { name, args } => {
+                let args = args.iter().map(|arg| quote(arg)).collect::<Vec<_>>();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Operand::List(values) => {
+                let values = values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(f, "({})", values.join(", "))
+            }
+            Operand::Empty => write!(f, "EMPTY"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(quote(r#"say "hi" \o/"#), r#""say \"hi\" \\o/""#);
+        assert_eq!(quote("two\nlines"), r#""two\nlines""#);
+
+        let clause = field("Story Points")
+            .gte(3)
+            .and(field("cf[10231]").is_not_empty())
+            .and(field("order").eq("o'neil"));
+
+        assert_eq!(
+            clause.to_string(),
+            r#""Story Points" >= 3 AND cf[10231] IS NOT EMPTY AND "order" = "o'neil""#
+        );
+
+        let clause = field("user").eq("chipp").and(field("start").lt(1));
+        assert_eq!(clause.to_string(), r#""user" = "chipp" AND "start" < 1"#);
+    }
+
+    #[test]
+    fn exact_numbers() {
+        let clause = field("id")
+            .eq(9007199254740993u64)
+            .and(field("estimate").lt(i64::MIN))
+            .and(field("ratio").gt(Operand::try_from(0.1).unwrap()));
+
+        assert_eq!(
+            clause.to_string(),
+            "id = 9007199254740993 AND estimate < -9223372036854775808 AND ratio > 0.1"
+        );
+
+        assert_eq!(Operand::try_from(f64::NAN), Err(NonFiniteNumber));
+        assert_eq!(Operand::try_from(f32::NEG_INFINITY), Err(NonFiniteNumber));
+    }
+
+    #[test]
+    fn precedence() {
+        let clause = field("project")
+            .eq("RS")
+            .and(
+                field("status")
+                    .eq("Open")
+                    .or(field("status").eq("Reopened")),
+            )
+            .and(
+                !field("labels")
+                    .is_in(["wontfix", "duplicate"])
+                    .or(field("labels").is_empty()),
+            );
+
+        assert_eq!(
+            clause.to_string(),
+            r#"project = "RS" AND (status = "Open" OR status = "Reopened") AND NOT (labels IN ("wontfix", "duplicate") OR labels IS EMPTY)"#
+        );
+
+        let clause = field("x").eq(1).and(field("y").eq(2)).or(field("z").eq(3));
+        assert_eq!(clause.to_string(), r#"x = 1 AND y = 2 OR z = 3"#);
+    }
+
+    #[test]
+    fn functions_and_ordering() {
+        let query = Jql::new(
+            field("assignee")
+                .is_in([current_user(), members_of("jira-developers")])
+                .and(field("updated").gte(start_of_day(Some("-7d")))),
+        )
+        .order_by("priority", Direction::Desc)
+        .order_by("Epic Link", Direction::Asc);
+
+        assert_eq!(
+            query.to_string(),
+            r#"assignee IN (currentUser(), membersOf("jira-developers")) AND updated >= startOfDay("-7d") ORDER BY priority DESC, "Epic Link" ASC"#
+        );
+
+        assert_eq!(
+            Jql::all().order_by("created", Direction::Asc).to_string(),
+            "ORDER BY created ASC"
+        );
+    }
+
+    #[test]
+    fn query_errors() {
+        let parsed: ParsedQueries = serde_json::from_str(
+            r#"{"queries": [
+                {"query": "project = RS", "structure": {"where": {}}},
+                {"query": "project = RS AND", "errors": ["Error in the JQL Query: Expecting either a field name or an opening parenthesis but got the end of the query. (line 1, character 17)"]}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert!(parsed.queries[0].is_valid());
+        assert!(!parsed.queries[1].is_valid());
+        assert!(parsed.queries[1].structure.is_none());
+
+        let error = &parsed.queries[1].errors[0];
+        assert_eq!(error.line, Some(1));
+        assert_eq!(error.character, Some(17));
+
+        let error =
+            QueryError::from("The value 'XX' does not exist for the field 'project'.".to_owned());
+        assert_eq!((error.line, error.character), (None, None));
+    }
+}
@@ -0,0 +1,587 @@
+use std::fmt;
+
+use super::{Clause, Direction, Jql, Operand, Operator, OrderBy};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Character offset in the query.
+    pub position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses JQL into the same AST the builder produces.
+///
+/// History predicates (`CHANGED`, `WAS ... BY`, `DURING`, ...) aren't supported.
+pub fn parse(query: &str) -> Result<Jql, ParseError> {
+    let tokens = tokenize(query)?;
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        end: query.chars().count(),
+    };
+
+    let clause = if parser.peek_keyword("ORDER") || parser.peek().is_none() {
+        None
+    } else {
+        Some(parser.or()?)
+    };
+
+    let order_by = if parser.eat_keyword("ORDER") {
+        parser.expect_keyword("BY")?;
+        parser.order_by()?
+    } else {
+        vec![]
+    };
+
+    match parser.peek() {
+        None => Ok(Jql { clause, order_by }),
+        Some(_) => Err(parser.error("unexpected input")),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    String(String),
+    Operator(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+const SYMBOLS: &[&str] = &[
+    "!=", ">=", "<=", "!~", "&&", "||", "=", ">", "<", "~", "!", "&", "|",
+];
+
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || "_.-/:[]@+*".contains(char)
+}
+
+fn tokenize(query: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars = query.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut index = 0;
+
+    while index < chars.len() {
+        let char = chars[index];
+        let start = index;
+
+        if char.is_whitespace() {
+            index += 1;
+            continue;
+        }
+
+        let token = match char {
+            '(' => {
+                index += 1;
+                Token::LParen
+            }
+            ')' => {
+                index += 1;
+                Token::RParen
+            }
+            ',' => {
+                index += 1;
+                Token::Comma
+            }
+            '"' | '\'' => {
+                let mut value = String::new();
+                index += 1;
+
+                loop {
+                    match chars.get(index) {
+                        None => {
+                            return Err(ParseError {
+                                message: "unterminated string".to_owned(),
+                                position: start,
+                            })
+                        }
+                        Some(quote) if *quote == char => {
+                            index += 1;
+                            break;
+                        }
+                        Some('\\') => {
+                            let escaped = match chars.get(index + 1) {
+                                Some('n') => '\n',
+                                Some('r') => '\r',
+                                Some('t') => '\t',
+                                Some(escaped) => *escaped,
+                                None => '\\',
+                            };
+                            value.push(escaped);
+                            index += 2;
+                        }
+                        Some(char) => {
+                            value.push(*char);
+                            index += 1;
+                        }
+                    }
+                }
+
+                Token::String(value)
+            }
+            char if is_word_char(char) => {
+                let word = chars[index..]
+                    .iter()
+                    .take_while(|char| is_word_char(**char))
+                    .collect::<String>();
+                index += word.chars().count();
+
+                Token::Word(word)
+            }
+            _ => {
+                let symbol = SYMBOLS.iter().find(|symbol| {
+                    symbol
+                        .chars()
+                        .enumerate()
+                        .all(|(offset, char)| chars.get(index + offset) == Some(&char))
+                });
+
+                match symbol {
+                    Some(symbol) => {
+                        index += symbol.len();
+                        Token::Operator(symbol)
+                    }
+                    None => {
+                        return Err(ParseError {
+                            message: format!("unexpected character '{}'", char),
+                            position: start,
+                        })
+                    }
+                }
+            }
+        };
+
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).map(|(token, _)| token.clone());
+        self.index += 1;
+        token
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let position = self
+            .tokens
+            .get(self.index)
+            .map_or(self.end, |(_, position)| *position);
+
+        ParseError {
+            message: message.to_owned(),
+            position,
+        }
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matches = self.peek_keyword(keyword);
+        if matches {
+            self.index += 1;
+        }
+        matches
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {}", keyword)))
+        }
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        let matches = self.peek() == Some(expected);
+        if matches {
+            self.index += 1;
+        }
+        matches
+    }
+
+    fn or(&mut self) -> Result<Clause, ParseError> {
+        let mut clause = self.and()?;
+
+        while self.eat_keyword("OR")
+            || self.eat(&Token::Operator("||"))
+            || self.eat(&Token::Operator("|"))
+        {
+            clause = clause.or(self.and()?);
+        }
+
+        Ok(clause)
+    }
+
+    fn and(&mut self) -> Result<Clause, ParseError> {
+        let mut clause = self.not()?;
+
+        while self.eat_keyword("AND")
+            || self.eat(&Token::Operator("&&"))
+            || self.eat(&Token::Operator("&"))
+        {
+            clause = clause.and(self.not()?);
+        }
+
+        Ok(clause)
+    }
+
+    fn not(&mut self) -> Result<Clause, ParseError> {
+        if self.eat_keyword("NOT") || self.eat(&Token::Operator("!")) {
+            Ok(!self.not()?)
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Clause, ParseError> {
+        if self.eat(&Token::LParen) {
+            let clause = self.or()?;
+
+            if !self.eat(&Token::RParen) {
+                return Err(self.error("expected ')'"));
+            }
+
+            // Grouping is implied by the tree, rendering adds parentheses back
+            // where precedence needs them.
+            return Ok(clause);
+        }
+
+        let field = self.field()?;
+        let operator = self.operator()?;
+        let operand = self.operand(operator)?;
+
+        Ok(Clause::Compare {
+            field,
+            operator,
+            operand,
+        })
+    }
+
+    fn field(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Token::Word(word)) if !is_keyword(word) => {
+                let word = word.clone();
+                self.index += 1;
+                Ok(word)
+            }
+            Some(Token::String(name)) => {
+                let name = name.clone();
+                self.index += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("expected a field")),
+        }
+    }
+
+    fn operator(&mut self) -> Result<Operator, ParseError> {
+        let operator = match self.peek() {
+            Some(Token::Operator(symbol)) => {
+                let operator = match *symbol {
+                    "=" => Operator::Eq,
+                    "!=" => Operator::NotEq,
+                    ">" => Operator::Gt,
+                    ">=" => Operator::Gte,
+                    "<" => Operator::Lt,
+                    "<=" => Operator::Lte,
+                    "~" => Operator::Contains,
+                    "!~" => Operator::NotContains,
+                    _ => return Err(self.error("expected an operator")),
+                };
+                self.index += 1;
+                operator
+            }
+            Some(Token::Word(_)) => {
+                if self.eat_keyword("IN") {
+                    Operator::In
+                } else if self.eat_keyword("NOT") {
+                    self.expect_keyword("IN")?;
+                    Operator::NotIn
+                } else if self.eat_keyword("IS") {
+                    if self.eat_keyword("NOT") {
+                        Operator::IsNot
+                    } else {
+                        Operator::Is
+                    }
+                } else if self.eat_keyword("WAS") {
+                    match (self.eat_keyword("NOT"), self.eat_keyword("IN")) {
+                        (false, false) => Operator::Was,
+                        (true, false) => Operator::WasNot,
+                        (false, true) => Operator::WasIn,
+                        (true, true) => Operator::WasNotIn,
+                    }
+                } else if self.peek_keyword("CHANGED") {
+                    return Err(self.error("CHANGED isn't supported"));
+                } else {
+                    return Err(self.error("expected an operator"));
+                }
+            }
+            _ => return Err(self.error("expected an operator")),
+        };
+
+        Ok(operator)
+    }
+
+    fn operand(&mut self, operator: Operator) -> Result<Operand, ParseError> {
+        let list = matches!(
+            operator,
+            Operator::In | Operator::NotIn | Operator::WasIn | Operator::WasNotIn
+        );
+
+        if list {
+            // Functions returning lists, e.g. `sprint IN openSprints()`.
+            if !self.eat(&Token::LParen) {
+                let position = self.index;
+
+                return match self.value() {
+                    Ok(function @ Operand::Function { .. }) => Ok(function),
+                    _ => {
+                        self.index = position;
+                        Err(self.error("expected '('"))
+                    }
+                };
+            }
+
+            let mut values = vec![self.value()?];
+            while self.eat(&Token::Comma) {
+                values.push(self.value()?);
+            }
+
+            if !self.eat(&Token::RParen) {
+                return Err(self.error("expected ')'"));
+            }
+
+            return Ok(Operand::List(values));
+        }
+
+        let operand = self.value()?;
+
+        if matches!(operator, Operator::Is | Operator::IsNot) && operand != Operand::Empty {
+            return Err(self.error("IS only accepts EMPTY or NULL"));
+        }
+
+        Ok(operand)
+    }
+
+    fn value(&mut self) -> Result<Operand, ParseError> {
+        let position = self.index;
+
+        match self.next() {
+            Some(Token::String(value)) => Ok(Operand::Value(value)),
+            Some(Token::Word(word)) => {
+                if word.eq_ignore_ascii_case("EMPTY") || word.eq_ignore_ascii_case("NULL") {
+                    return Ok(Operand::Empty);
+                }
+
+                if self.eat(&Token::LParen) {
+                    return self.function(word);
+                }
+
+                if is_keyword(&word) {
+                    self.index = position;
+                    return Err(self.error("expected a value"));
+                }
+
+                Ok(Operand::Word(word))
+            }
+            _ => {
+                self.index = position;
+                Err(self.error("expected a value"))
+            }
+        }
+    }
+
+    fn function(&mut self, name: String) -> Result<Operand, ParseError> {
+        let mut args = vec![];
+
+        if !self.eat(&Token::RParen) {
+            loop {
+                match self.next() {
+                    Some(Token::String(arg) | Token::Word(arg)) => args.push(arg),
+                    _ => {
+                        self.index -= 1;
+                        return Err(self.error("expected a function argument"));
+                    }
+                }
+
+                if self.eat(&Token::RParen) {
+                    break;
+                }
+
+                if !self.eat(&Token::Comma) {
+                    return Err(self.error("expected ',' or ')'"));
+                }
+            }
+        }
+
+        Ok(Operand::Function { name, args })
+    }
+
+    fn order_by(&mut self) -> Result<Vec<OrderBy>, ParseError> {
+        let mut order_by = vec![];
+
+        loop {
+            let field = self.field()?;
+
+            let direction = if self.eat_keyword("ASC") {
+                Some(Direction::Asc)
+            } else if self.eat_keyword("DESC") {
+                Some(Direction::Desc)
+            } else {
+                None
+            };
+
+            order_by.push(OrderBy { field, direction });
+
+            if !self.eat(&Token::Comma) {
+                return Ok(order_by);
+            }
+        }
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    [
+        "AND", "OR", "NOT", "IN", "IS", "WAS", "ORDER", "BY", "EMPTY", "NULL",
+    ]
+    .iter()
+    .any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jql::{field, function};
+
+    #[test]
+    fn round_trip() {
+        let queries = [
+            r#"project = "RS" AND status IN ("Open", "In Progress") ORDER BY priority DESC, created"#,
+            r#"assignee = currentUser() AND updated >= startOfDay("-7d")"#,
+            r#"cf[10231] IS NOT EMPTY AND "Story Points" > 3"#,
+            r#"NOT (labels IN ("wontfix") OR labels IS EMPTY) AND status WAS NOT IN ("Done")"#,
+            r#"summary ~ "say \"hello\"" OR description !~ "draft""#,
+            "ORDER BY created ASC",
+            "project = RS AND status WAS Done",
+            "fixVersion = 1.10 AND fixVersion IN (2.0, 1.10)",
+            "priority = 007 AND key = 1e3 AND id = 12345678901234567890123",
+        ];
+
+        for query in queries {
+            assert_eq!(parse(query).unwrap().to_string(), query);
+        }
+    }
+
+    #[test]
+    fn parses_loose_syntax() {
+        let query = parse(
+            "project=RS and (status = 'In Progress' || status was Done) & !resolution is null \
+             and sprint in openSprints() and assignee in (membersOf(devs), bob) order by Rank asc",
+        )
+        .unwrap();
+
+        let word = |word: &str| Operand::Word(word.to_owned());
+
+        let expected = field("project")
+            .eq(word("RS"))
+            .and(
+                field("status")
+                    .eq("In Progress")
+                    .or(field("status").was(word("Done"))),
+            )
+            .and(!field("resolution").is_empty())
+            .and(
+                field("sprint")
+                    .compare(Operator::In, function("openSprints", Vec::<String>::new())),
+            )
+            .and(field("assignee").is_in([function("membersOf", ["devs"]), word("bob")]));
+
+        assert_eq!(query.clause, Some(expected));
+        assert_eq!(
+            query.order_by,
+            vec![OrderBy {
+                field: "Rank".to_owned(),
+                direction: Some(Direction::Asc),
+            }]
+        );
+
+        assert_eq!(
+            parse("x = 1 OR y = 2 AND z = 3").unwrap().to_string(),
+            "x = 1 OR y = 2 AND z = 3"
+        );
+        assert_eq!(
+            parse("(x = 1 OR y = 2) AND z = 3").unwrap().to_string(),
+            "(x = 1 OR y = 2) AND z = 3"
+        );
+        assert_eq!(parse("  ").unwrap(), Jql::all());
+    }
+
+    #[test]
+    fn errors() {
+        let error = |query| parse(query).unwrap_err();
+
+        assert_eq!(
+            error("status = \"Open"),
+            ParseError {
+                message: "unterminated string".to_owned(),
+                position: 9,
+            }
+        );
+        assert_eq!(error("status = Open AND").position, 17);
+        assert_eq!(error("status Open").message, "expected an operator");
+        assert_eq!(error("status IN Open").message, "expected '('");
+        assert_eq!(error("(status = Open").message, "expected ')'");
+        assert_eq!(error("status = Open ORDER created").message, "expected BY");
+        assert_eq!(
+            error("status IS Open").message,
+            "IS only accepts EMPTY or NULL"
+        );
+        assert_eq!(error("status = Open status = Done").position, 14);
+        assert_eq!(
+            error("status = Open; drop").message,
+            "unexpected character ';'"
+        );
+    }
+
+    #[test]
+    fn rewriting() {
+        let query: Jql =
+            "assignee = currentUser() OR reporter = currentUser() ORDER BY updated DESC"
+                .parse()
+                .unwrap();
+
+        assert_eq!(query.fields(), ["assignee", "reporter", "updated"]);
+
+        let scoped = query.and(field("project").eq("RS"));
+        assert_eq!(
+            scoped.to_string(),
+            r#"(assignee = currentUser() OR reporter = currentUser()) AND project = "RS" ORDER BY updated DESC"#
+        );
+
+        let scoped = Jql::all().and(field("project").eq("RS"));
+        assert_eq!(scoped.to_string(), r#"project = "RS""#);
+    }
+}
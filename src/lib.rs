@@ -1,30 +1,73 @@
+pub mod adf;
+
+pub mod attachment;
+pub use attachment::Attachment;
+
 pub mod board;
-pub use board::Board;
+pub use board::{Board, BoardConfiguration, BoardFilter, BoardType};
+
+pub mod builder;
+pub use builder::ClientBuilder;
 
 pub mod changelog;
 pub use changelog::Changelog;
 
+pub mod comment;
+pub use comment::{Comment, Comments};
+
 pub mod client;
 pub use client::Client as JiraClient;
 
+pub mod credentials;
+pub use credentials::CredentialProvider;
+
 pub mod date_format;
 
 pub mod dev_status;
 pub use dev_status::{DevStatus, PullRequest};
 
+pub mod error;
+pub use error::JiraError;
+
+pub mod field;
+pub use field::{CustomField, FieldRegistry};
+
+pub mod graph;
+pub use graph::IssueGraph;
+
 pub mod issue;
-pub use issue::{Fields, Issue, IssueStatus, IssueType, ShortIssue};
+pub use issue::{
+    CreateFields, Expand, FieldSet, Fields, Issue, IssueStatus, IssueType, ShortIssue,
+};
+
+pub mod jql;
+pub use jql::Jql;
+
+pub mod oauth;
+pub use oauth::OAuthConfig;
+
+pub mod pagination;
+pub use pagination::PageOptions;
 
 pub mod project;
 pub use project::Project;
 
+pub mod rate_limit;
+pub use rate_limit::RateLimit;
+
+pub mod retry;
+pub use retry::{RetryEvent, RetryPolicy};
+
 pub mod sprint;
-pub use sprint::Sprint;
+pub use sprint::{NewSprint, Sprint, SprintState, SprintUpdate};
 
 pub mod tempo_log;
 
+pub mod transition;
+pub use transition::Transition;
+
 pub mod user;
-pub use user::User;
+pub use user::{User, UserId};
 
 pub mod worklog;
-pub use worklog::{Worklog, Worklogs};
+pub use worklog::{AdjustEstimate, Worklog, WorklogChanges, WorklogCursor, Worklogs};
@@ -0,0 +1,344 @@
+use std::collections::VecDeque;
+use std::future::Future;
+
+use futures_util::stream::{self, FuturesOrdered, Stream, StreamExt};
+
+use crate::client::{AgilePageResponse, IssuesPageResponse};
+use crate::comment::{Comment, Comments};
+use crate::error::JiraError;
+use crate::issue::Issue;
+use crate::worklog::{Worklog, Worklogs};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageOptions {
+    pub page_size: u32,
+    /// How many pages may be requested at the same time once the first page is known.
+    pub concurrency: usize,
+}
+
+impl PageOptions {
+    pub fn new(page_size: u32, concurrency: usize) -> PageOptions {
+        PageOptions {
+            page_size,
+            concurrency,
+        }
+    }
+}
+
+impl Default for PageOptions {
+    fn default() -> PageOptions {
+        PageOptions {
+            page_size: 50,
+            concurrency: 1,
+        }
+    }
+}
+
+pub trait Page {
+    type Item;
+
+    fn max_results(&self) -> u32;
+
+    fn total(&self) -> Option<u32> {
+        None
+    }
+
+    fn is_last(&self) -> bool {
+        false
+    }
+
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+impl Page for IssuesPageResponse {
+    type Item = Issue;
+
+    fn max_results(&self) -> u32 {
+        self.max_results
+    }
+
+    fn total(&self) -> Option<u32> {
+        Some(self.total)
+    }
+
+    fn into_items(self) -> Vec<Issue> {
+        self.issues
+    }
+}
+
+impl<V> Page for AgilePageResponse<V> {
+    type Item = V;
+
+    fn max_results(&self) -> u32 {
+        self.max_results as u32
+    }
+
+    fn is_last(&self) -> bool {
+        self.is_last
+    }
+
+    fn into_items(self) -> Vec<V> {
+        self.values
+    }
+}
+
+impl Page for Worklogs {
+    type Item = Worklog;
+
+    fn max_results(&self) -> u32 {
+        self.max_results
+    }
+
+    fn total(&self) -> Option<u32> {
+        Some(self.total)
+    }
+
+    fn into_items(self) -> Vec<Worklog> {
+        self.worklogs
+    }
+}
+
+impl Page for Comments {
+    type Item = Comment;
+
+    fn max_results(&self) -> u32 {
+        self.max_results
+    }
+
+    fn total(&self) -> Option<u32> {
+        Some(self.total)
+    }
+
+    fn into_items(self) -> Vec<Comment> {
+        self.comments
+    }
+}
+
+struct Paginator<F, Fut: Future> {
+    fetch: F,
+    in_flight: FuturesOrdered<Fut>,
+    in_flight_starts: VecDeque<u32>,
+    next_start: u32,
+    page_size: u32,
+    concurrency: usize,
+    total: Option<u32>,
+    first_page_loaded: bool,
+    finished: bool,
+}
+
+impl<F, Fut, P> Paginator<F, Fut>
+where
+    F: Fn(u32, u32) -> Fut,
+    Fut: Future<Output = Result<P, JiraError>>,
+    P: Page,
+{
+    fn new(options: PageOptions, fetch: F) -> Self {
+        Paginator {
+            fetch,
+            in_flight: FuturesOrdered::new(),
+            in_flight_starts: VecDeque::new(),
+            next_start: 0,
+            page_size: options.page_size.max(1),
+            concurrency: options.concurrency.max(1),
+            total: None,
+            first_page_loaded: false,
+            finished: false,
+        }
+    }
+
+    fn fill(&mut self) {
+        // Until the first page arrives we don't know the real page size, as
+        // Jira may cap `maxResults` below what we asked for.
+        let limit = if self.first_page_loaded {
+            self.concurrency
+        } else {
+            1
+        };
+
+        while self.in_flight.len() < limit {
+            if matches!(self.total, Some(total) if self.next_start >= total) {
+                break;
+            }
+
+            self.in_flight_starts.push_back(self.next_start);
+            self.in_flight
+                .push_back((self.fetch)(self.next_start, self.page_size));
+            self.next_start += self.page_size;
+        }
+    }
+
+    async fn next_page(&mut self) -> Option<Result<Vec<P::Item>, JiraError>> {
+        if self.finished {
+            return None;
+        }
+
+        self.fill();
+
+        let page = self.in_flight.next().await?;
+        let start_at = self.in_flight_starts.pop_front().unwrap_or_default();
+
+        let page = match page {
+            Ok(page) => page,
+            Err(err) => {
+                self.finish();
+                return Some(Err(err));
+            }
+        };
+
+        if !self.first_page_loaded {
+            self.first_page_loaded = true;
+
+            if page.max_results() > 0 {
+                self.page_size = page.max_results();
+            }
+
+            self.next_start = start_at + self.page_size;
+        }
+
+        if let Some(total) = page.total() {
+            self.total = Some(total);
+        }
+
+        let reached_total = matches!(self.total, Some(total) if start_at + self.page_size >= total);
+        let is_last = page.is_last();
+
+        let items = page.into_items();
+
+        if is_last || items.is_empty() || reached_total {
+            self.finish();
+        }
+
+        Some(Ok(items))
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+        self.in_flight = FuturesOrdered::new();
+        self.in_flight_starts.clear();
+    }
+}
+
+/// Turns a page loader into a stream of every item across all pages.
+///
+/// `fetch` receives `startAt` and `maxResults` and is called for the first
+/// page alone, then for up to `options.concurrency` pages at a time.
+pub(crate) fn paginate<'a, F, Fut, P>(
+    options: PageOptions,
+    fetch: F,
+) -> impl Stream<Item = Result<P::Item, JiraError>> + 'a
+where
+    F: Fn(u32, u32) -> Fut + 'a,
+    Fut: Future<Output = Result<P, JiraError>> + 'a,
+    P: Page + 'a,
+    P::Item: 'a,
+{
+    let paginator = Paginator::new(options, fetch);
+
+    stream::unfold(paginator, |mut paginator| async move {
+        let page = paginator.next_page().await?;
+        Some((page, paginator))
+    })
+    .flat_map(|items| {
+        let items = match items {
+            Ok(items) => items.into_iter().map(Ok).collect(),
+            Err(err) => vec![Err(err)],
+        };
+
+        stream::iter(items)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_executor::block_on;
+    use std::cell::RefCell;
+
+    struct TestPage {
+        max_results: u32,
+        total: Option<u32>,
+        is_last: bool,
+        items: Vec<u32>,
+    }
+
+    impl Page for TestPage {
+        type Item = u32;
+
+        fn max_results(&self) -> u32 {
+            self.max_results
+        }
+
+        fn total(&self) -> Option<u32> {
+            self.total
+        }
+
+        fn is_last(&self) -> bool {
+            self.is_last
+        }
+
+        fn into_items(self) -> Vec<u32> {
+            self.items
+        }
+    }
+
+    fn page_with_total(start_at: u32, max_results: u32, total: u32) -> TestPage {
+        let end = (start_at + max_results).min(total);
+
+        TestPage {
+            max_results,
+            total: Some(total),
+            is_last: false,
+            items: (start_at..end).collect(),
+        }
+    }
+
+    #[test]
+    fn paginates_by_total() {
+        let requests = RefCell::new(vec![]);
+
+        let stream = paginate(PageOptions::new(10, 3), |start_at, max_results| {
+            requests.borrow_mut().push(start_at);
+            async move { Ok(page_with_total(start_at, max_results, 35)) }
+        });
+
+        let items: Vec<u32> = block_on(stream.map(Result::unwrap).collect());
+
+        assert_eq!(items, (0..35).collect::<Vec<_>>());
+        assert_eq!(*requests.borrow(), vec![0, 10, 20, 30]);
+    }
+
+    #[test]
+    fn respects_capped_page_size() {
+        let requests = RefCell::new(vec![]);
+
+        let stream = paginate(PageOptions::new(100, 2), |start_at, _| {
+            requests.borrow_mut().push(start_at);
+            async move { Ok(page_with_total(start_at, 20, 50)) }
+        });
+
+        let items: Vec<u32> = block_on(stream.map(Result::unwrap).collect());
+
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+        assert_eq!(*requests.borrow(), vec![0, 20, 40]);
+    }
+
+    #[test]
+    fn paginates_until_is_last() {
+        let stream = paginate(PageOptions::new(2, 4), |start_at, max_results| async move {
+            Ok(TestPage {
+                max_results,
+                total: None,
+                is_last: start_at >= 4,
+                items: if start_at > 4 {
+                    vec![]
+                } else {
+                    vec![start_at, start_at + 1]
+                },
+            })
+        });
+
+        let items: Vec<u32> = block_on(stream.map(Result::unwrap).collect());
+
+        assert_eq!(items, vec![0, 1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::retry::sleep;
+
+/// Client side limit shared by all requests of a `Client`.
+///
+/// Requests are spaced by a token bucket refilled at `requests_per_second`
+/// that holds at most `burst` tokens, and at most `max_in_flight` requests
+/// are sent concurrently.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    pub burst: u32,
+    pub max_in_flight: Option<usize>,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_second: f64) -> RateLimit {
+        RateLimit {
+            requests_per_second,
+            burst: 1,
+            max_in_flight: None,
+        }
+    }
+
+    pub fn burst(mut self, burst: u32) -> RateLimit {
+        self.burst = burst.max(1);
+        self
+    }
+
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> RateLimit {
+        self.max_in_flight = Some(max_in_flight.max(1));
+        self
+    }
+}
+
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    bucket: Mutex<Bucket>,
+    slots: Option<Slots>,
+}
+
+struct Bucket {
+    // Negative when requests reserved tokens ahead of time.
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> RateLimiter {
+        RateLimiter {
+            limit,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(limit.burst),
+                updated_at: Instant::now(),
+            }),
+            slots: limit.max_in_flight.map(Slots::new),
+        }
+    }
+
+    /// Waits for a free slot and a token, the slot is held until the permit is dropped.
+    pub(crate) async fn acquire(&self) -> Option<Permit<'_>> {
+        let permit = match &self.slots {
+            Some(slots) => Some(slots.acquire().await),
+            None => None,
+        };
+
+        sleep(self.reserve(Instant::now())).await;
+
+        permit
+    }
+
+    /// Delays all following requests, e.g. when Jira responded with `Retry-After`.
+    pub(crate) fn pause(&self, delay: Duration) {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket, Instant::now());
+
+        let paused = -delay.as_secs_f64() * self.limit.requests_per_second;
+        bucket.tokens = bucket.tokens.min(paused);
+    }
+
+    // Takes a token, possibly one that isn't there yet, and returns how long
+    // to wait until it is.
+    fn reserve(&self, now: Instant) -> Duration {
+        if self.limit.requests_per_second <= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket, now);
+
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.limit.requests_per_second)
+        }
+    }
+
+    fn refill(&self, bucket: &mut Bucket, now: Instant) {
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        let tokens = bucket.tokens + elapsed.as_secs_f64() * self.limit.requests_per_second;
+
+        bucket.tokens = tokens.min(f64::from(self.limit.burst));
+        bucket.updated_at = now.max(bucket.updated_at);
+    }
+}
+
+struct Slots {
+    state: Mutex<SlotsState>,
+}
+
+struct SlotsState {
+    available: usize,
+    waiters: VecDeque<Waker>,
+}
+
+impl Slots {
+    fn new(count: usize) -> Slots {
+        Slots {
+            state: Mutex::new(SlotsState {
+                available: count,
+                waiters: VecDeque::new(),
+            }),
+        }
+    }
+
+    fn acquire(&self) -> Acquire<'_> {
+        Acquire { slots: self }
+    }
+}
+
+struct Acquire<'a> {
+    slots: &'a Slots,
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = Permit<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Permit<'a>> {
+        let mut state = self.slots.state.lock().unwrap();
+
+        if state.available > 0 {
+            state.available -= 1;
+            Poll::Ready(Permit { slots: self.slots })
+        } else {
+            state.waiters.push_back(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+pub(crate) struct Permit<'a> {
+    slots: &'a Slots,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        let waiters = {
+            let mut state = self.slots.state.lock().unwrap();
+            state.available += 1;
+            std::mem::take(&mut state.waiters)
+        };
+
+        // Waiters may have been dropped, wake all of them to not lose the slot.
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::task::noop_waker;
+
+    #[test]
+    fn token_bucket() {
+        let limiter = RateLimiter::new(RateLimit::new(10.0).burst(2));
+        let start = limiter.bucket.lock().unwrap().updated_at;
+
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::from_millis(100));
+        assert_eq!(limiter.reserve(start), Duration::from_millis(200));
+
+        // Two tokens were borrowed, a second later the bucket is full again.
+        let later = start + Duration::from_secs(1);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn pause() {
+        let limiter = RateLimiter::new(RateLimit::new(10.0).burst(5));
+        limiter.pause(Duration::from_secs(2));
+
+        let now = limiter.bucket.lock().unwrap().updated_at;
+        let delay = limiter.reserve(now);
+
+        assert!(delay >= Duration::from_millis(2000) && delay <= Duration::from_millis(2101));
+    }
+
+    #[test]
+    fn max_in_flight() {
+        let slots = Slots::new(2);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let first = slots.acquire();
+        let second = slots.acquire();
+        let mut third = slots.acquire();
+
+        let first = futures_executor::block_on(first);
+        let _second = futures_executor::block_on(second);
+
+        assert!(Pin::new(&mut third).poll(&mut cx).is_pending());
+
+        drop(first);
+        assert!(Pin::new(&mut third).poll(&mut cx).is_ready());
+    }
+}
@@ -0,0 +1,408 @@
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Condvar, Mutex, Once, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use futures_channel::oneshot;
+
+use chipp_http::HttpMethod;
+use log::warn;
+use url::Url;
+
+use crate::error::JiraError;
+
+/// When and how often failed requests are repeated.
+///
+/// Transport errors, 429, 502, 503 and 504 responses are retried with
+/// jittered exponential backoff, or after the delay Jira asks for in
+/// `Retry-After`. A `Retry-After` beyond `max_delay` fails the request
+/// right away instead of stalling the caller.
+///
+/// `GET`, `PUT` and `DELETE` requests are idempotent and safe to repeat.
+/// `POST` requests may create duplicates and are attempted once unless
+/// `mutating_attempts` is raised, endpoints that only read via `POST`, like
+/// search, are treated as idempotent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub idempotent_attempts: u8,
+    pub mutating_attempts: u8,
+    /// Delay before the first retry, doubled for every next one.
+    pub base_delay: Duration,
+    /// Upper bound of the backoff and of the accepted `Retry-After`.
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(idempotent_attempts: u8, mutating_attempts: u8) -> RetryPolicy {
+        RetryPolicy {
+            idempotent_attempts,
+            mutating_attempts,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    pub fn never() -> RetryPolicy {
+        RetryPolicy::new(1, 1)
+    }
+
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> RetryPolicy {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn attempts(&self, kind: MethodKind) -> u8 {
+        match kind {
+            MethodKind::Idempotent => self.idempotent_attempts,
+            MethodKind::Mutating => self.mutating_attempts,
+        }
+        .max(1)
+    }
+
+    /// Delay after the failed `attempt`, starting from 1: half of the
+    /// exponential delay is fixed, the other half is random.
+    pub fn backoff(&self, attempt: u8) -> Duration {
+        let exponent = u32::from(attempt.saturating_sub(1)).min(16);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        let half = delay / 2;
+        half + half.mul_f64(jitter())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::new(3, 1)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MethodKind {
+    Idempotent,
+    Mutating,
+}
+
+impl MethodKind {
+    pub fn of(method: &HttpMethod) -> MethodKind {
+        match method {
+            HttpMethod::Get | HttpMethod::Put | HttpMethod::Delete => MethodKind::Idempotent,
+            HttpMethod::Post => MethodKind::Mutating,
+        }
+    }
+}
+
+/// Passed to the `ClientBuilder::on_retry` hook before waiting for the next attempt.
+#[derive(Debug)]
+pub struct RetryEvent<'a> {
+    pub url: &'a Url,
+    /// The attempt that failed, starting from 1.
+    pub attempt: u8,
+    pub max_attempts: u8,
+    pub delay: Duration,
+    pub error: &'a JiraError,
+}
+
+pub(crate) type RetryHook = Arc<dyn Fn(&RetryEvent) + Send + Sync>;
+
+pub(crate) fn is_retryable(error: &JiraError) -> bool {
+    match error {
+        JiraError::RateLimited { .. }
+        | JiraError::ServiceUnavailable { .. }
+        | JiraError::Transport(_) => true,
+        JiraError::Http { status, .. } => matches!(status, 502 | 504),
+        _ => false,
+    }
+}
+
+/// Runs `operation` until it succeeds, fails with a non-retryable error or
+/// runs out of attempts.
+pub(crate) async fn retry<T, F, Fut>(
+    policy: &RetryPolicy,
+    kind: MethodKind,
+    url: &Url,
+    hook: Option<&RetryHook>,
+    mut operation: F,
+) -> Result<T, JiraError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, JiraError>>,
+{
+    let max_attempts = policy.attempts(kind);
+    let mut attempt = 1;
+
+    loop {
+        let error = match operation().await {
+            Err(error) if attempt < max_attempts && is_retryable(&error) => error,
+            result => return result,
+        };
+
+        let delay = match error.retry_after() {
+            Some(delay) if delay > policy.max_delay => return Err(error),
+            Some(delay) => delay,
+            None => policy.backoff(attempt),
+        };
+
+        warn!(
+            "request {} failed on attempt {}/{}: {}, retrying in {} ms",
+            url,
+            attempt,
+            max_attempts,
+            error,
+            delay.as_millis()
+        );
+
+        if let Some(hook) = hook {
+            hook(&RetryEvent {
+                url,
+                attempt,
+                max_attempts,
+                delay,
+                error: &error,
+            });
+        }
+
+        sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Runtime agnostic timer, a single thread wakes up every pending sleep.
+pub(crate) async fn sleep(duration: Duration) {
+    if duration.is_zero() {
+        return;
+    }
+
+    let (tx, rx) = oneshot::channel();
+    Timer::shared().schedule(Instant::now() + duration, tx);
+
+    let _ = rx.await;
+}
+
+#[derive(Default)]
+struct Timer {
+    pending: Mutex<Pending>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct Pending {
+    // Keyed by the deadline and a sequence number for equal deadlines.
+    sleeps: BTreeMap<(Instant, u64), oneshot::Sender<()>>,
+    sequence: u64,
+}
+
+impl Timer {
+    fn shared() -> &'static Timer {
+        static TIMER: OnceLock<Timer> = OnceLock::new();
+        static STARTED: Once = Once::new();
+
+        let timer = TIMER.get_or_init(Timer::default);
+
+        STARTED.call_once(|| {
+            thread::Builder::new()
+                .name("jira-api-timer".to_owned())
+                .spawn(|| timer.run())
+                .expect("can't start the timer thread");
+        });
+
+        timer
+    }
+
+    fn schedule(&self, deadline: Instant, tx: oneshot::Sender<()>) {
+        let mut pending = self.pending.lock().unwrap();
+
+        pending.sequence += 1;
+        let sequence = pending.sequence;
+        pending.sleeps.insert((deadline, sequence), tx);
+
+        self.changed.notify_one();
+    }
+
+    fn run(&self) {
+        let mut pending = self.pending.lock().unwrap();
+
+        loop {
+            let now = Instant::now();
+
+            while let Some(entry) = pending.sleeps.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+
+                // The sleep may have been dropped in the meantime.
+                let _ = entry.remove().send(());
+            }
+
+            pending = match pending.sleeps.keys().next() {
+                Some(&(deadline, _)) => {
+                    let timeout = deadline.saturating_duration_since(now);
+                    self.changed.wait_timeout(pending, timeout).unwrap().0
+                }
+                None => self.changed.wait(pending).unwrap(),
+            };
+        }
+    }
+}
+
+// Uniformly distributed in [0, 1), seeded by the randomly keyed std hasher.
+fn jitter() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_executor::block_on;
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    fn url() -> Url {
+        Url::parse("https://jira.example.io/rest/api/2/issue/RS-1").unwrap()
+    }
+
+    #[test]
+    fn attempts_by_method() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.attempts(MethodKind::of(&HttpMethod::Get)), 3);
+        assert_eq!(policy.attempts(MethodKind::of(&HttpMethod::Delete)), 3);
+        assert_eq!(policy.attempts(MethodKind::of(&HttpMethod::Post)), 1);
+        assert_eq!(RetryPolicy::new(0, 0).attempts(MethodKind::Idempotent), 1);
+    }
+
+    #[test]
+    fn backoff_bounds() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(1000));
+
+        for _ in 0..100 {
+            let first = policy.backoff(1);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+
+            let third = policy.backoff(3);
+            assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+
+            let capped = policy.backoff(20);
+            assert!(capped >= Duration::from_millis(500) && capped <= Duration::from_millis(1000));
+        }
+    }
+
+    #[test]
+    fn retries_rate_limited_requests() {
+        let policy = RetryPolicy::new(3, 1).with_backoff(Duration::ZERO, Duration::from_millis(10));
+        let calls = AtomicU8::new(0);
+        let events = Arc::new(std::sync::Mutex::new(vec![]));
+
+        let hook: RetryHook = {
+            let events = events.clone();
+            Arc::new(move |event: &RetryEvent| {
+                events.lock().unwrap().push((event.attempt, event.delay))
+            })
+        };
+
+        let result = block_on(retry(
+            &policy,
+            MethodKind::Idempotent,
+            &url(),
+            Some(&hook),
+            || async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(JiraError::RateLimited {
+                        retry_after: Some(Duration::from_millis(5)),
+                    }),
+                    1 => Err(JiraError::ServiceUnavailable { retry_after: None }),
+                    _ => Ok("issue"),
+                }
+            },
+        ));
+
+        assert_eq!(result.unwrap(), "issue");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(1, Duration::from_millis(5)), (2, Duration::ZERO)]
+        );
+    }
+
+    #[test]
+    fn doesnt_retry_mutating_or_client_errors() {
+        let policy = RetryPolicy::default().with_backoff(Duration::ZERO, Duration::ZERO);
+        let calls = AtomicU8::new(0);
+
+        let result: Result<(), _> = block_on(retry(
+            &policy,
+            MethodKind::Mutating,
+            &url(),
+            None,
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(JiraError::ServiceUnavailable { retry_after: None })
+            },
+        ));
+
+        assert!(matches!(result, Err(JiraError::ServiceUnavailable { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let result: Result<(), _> = block_on(retry(
+            &policy,
+            MethodKind::Idempotent,
+            &url(),
+            None,
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(JiraError::NotFound(Default::default()))
+            },
+        ));
+
+        assert!(matches!(result, Err(JiraError::NotFound(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn fails_on_long_retry_after() {
+        let policy = RetryPolicy::default().with_backoff(Duration::ZERO, Duration::from_secs(30));
+        let calls = AtomicU8::new(0);
+
+        let result: Result<(), _> = block_on(retry(
+            &policy,
+            MethodKind::Idempotent,
+            &url(),
+            None,
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(JiraError::RateLimited {
+                    retry_after: Some(Duration::from_secs(86400)),
+                })
+            },
+        ));
+
+        assert!(matches!(result, Err(JiraError::RateLimited { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn shared_timer() {
+        let started = Instant::now();
+        let order = Mutex::new(vec![]);
+
+        let sleeps = [30, 10, 20].map(|millis| {
+            let order = &order;
+            async move {
+                sleep(Duration::from_millis(millis)).await;
+                order.lock().unwrap().push(millis);
+            }
+        });
+        block_on(futures_util::future::join_all(sleeps));
+
+        assert!(started.elapsed() >= Duration::from_millis(30));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(*order.lock().unwrap(), [10, 20, 30]);
+    }
+}
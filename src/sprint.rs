@@ -1,14 +1,19 @@
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use log::warn;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
 
 #[derive(Clone, Deserialize, Debug, PartialEq, PartialOrd, Eq, Ord)]
 #[serde(rename_all = "camelCase")]
 pub struct Sprint {
-    pub id: u16,
+    pub id: u64,
     pub name: String,
     pub state: String,
 
-    pub origin_board_id: u16,
+    /// `None` for sprints whose board was deleted.
+    #[serde(default, alias = "boardId", alias = "rapidViewId")]
+    pub origin_board_id: Option<u64>,
 
     #[serde(default)]
     #[serde(deserialize_with = "crate::date_format::deserialize_optional_date_without_tz")]
@@ -21,6 +26,69 @@ pub struct Sprint {
     #[serde(default)]
     #[serde(deserialize_with = "crate::date_format::deserialize_optional_date_without_tz")]
     pub complete_date: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub goal: Option<String>,
+}
+
+#[derive(Clone, Copy, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SprintState {
+    Future,
+    Active,
+    Closed,
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSprint {
+    pub name: String,
+    pub origin_board_id: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "crate::date_format::serialize_optional_date_without_tz")]
+    pub start_date: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "crate::date_format::serialize_optional_date_without_tz")]
+    pub end_date: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
+}
+
+impl NewSprint {
+    pub fn new<N: ToString>(name: N, origin_board_id: u64) -> NewSprint {
+        NewSprint {
+            name: name.to_string(),
+            origin_board_id,
+            start_date: None,
+            end_date: None,
+            goal: None,
+        }
+    }
+}
+
+/// Partial sprint update, only the fields that are set are sent to Jira.
+#[derive(Clone, Default, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<SprintState>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "crate::date_format::serialize_optional_date_without_tz")]
+    pub start_date: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "crate::date_format::serialize_optional_date_without_tz")]
+    pub end_date: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
 }
 
 use std::fmt;
@@ -30,6 +98,131 @@ impl fmt::Display for Sprint {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSprintError(String);
+
+impl fmt::Display for ParseSprintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid sprint: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseSprintError {}
+
+/// Parses the legacy Jira Server encoding of the sprint custom field:
+/// `com.atlassian.greenhopper.service.sprint.Sprint@1f2e[id=1001,rapidViewId=123,state=CLOSED,...]`.
+impl FromStr for Sprint {
+    type Err = ParseSprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason: &str| ParseSprintError(format!("{reason} in {s:?}"));
+
+        let body = s
+            .find('[')
+            .zip(s.rfind(']'))
+            .and_then(|(start, end)| s.get(start + 1..end))
+            .ok_or_else(|| error("missing attributes"))?;
+
+        let attributes = split_attributes(body)
+            .into_iter()
+            .filter_map(|attribute| attribute.split_once('='))
+            .filter(|(_, value)| !value.is_empty() && *value != "<null>")
+            .collect::<HashMap<_, _>>();
+
+        let number = |key: &str| -> Result<u64, ParseSprintError> {
+            attributes
+                .get(key)
+                .ok_or_else(|| error(&format!("missing {key}")))?
+                .parse()
+                .map_err(|_| error(&format!("invalid {key}")))
+        };
+
+        let date = |key: &str| -> Result<Option<DateTime<Utc>>, ParseSprintError> {
+            attributes
+                .get(key)
+                .map(|value| DateTime::parse_from_rfc3339(value).map(|date| date.into()))
+                .transpose()
+                .map_err(|_| error(&format!("invalid {key}")))
+        };
+
+        Ok(Sprint {
+            id: number("id")?,
+            name: attributes.get("name").unwrap_or(&"").to_string(),
+            state: attributes
+                .get("state")
+                .ok_or_else(|| error("missing state"))?
+                .to_lowercase(),
+            origin_board_id: attributes
+                .contains_key("rapidViewId")
+                .then(|| number("rapidViewId"))
+                .transpose()?,
+            start_date: date("startDate")?,
+            end_date: date("endDate")?,
+            complete_date: date("completeDate")?,
+            goal: attributes.get("goal").map(ToString::to_string),
+        })
+    }
+}
+
+// Splits on commas that start a new `key=` pair, so sprint names may contain commas.
+fn split_attributes(body: &str) -> Vec<&str> {
+    let mut attributes = vec![];
+    let mut start = 0;
+
+    for (index, _) in body.match_indices(',') {
+        let rest = &body[index + 1..];
+        let key_len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+
+        if key_len > 0 && rest[key_len..].starts_with('=') {
+            attributes.push(&body[start..index]);
+            start = index + 1;
+        }
+    }
+
+    attributes.push(&body[start..]);
+    attributes
+}
+
+/// Accepts both the legacy string and the JSON object form of the sprint custom field.
+///
+/// Entries that can't be parsed are skipped, so one odd sprint doesn't fail
+/// the whole issue.
+pub(crate) fn deserialize_optional_sprints<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<Sprint>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SprintValue {
+        Legacy(String),
+        Object(Sprint),
+        Other(serde_json::Value),
+    }
+
+    let sprints = Option::<Vec<SprintValue>>::deserialize(deserializer)?;
+
+    Ok(sprints.map(|sprints| {
+        sprints
+            .into_iter()
+            .filter_map(|sprint| match sprint {
+                SprintValue::Legacy(sprint) => sprint
+                    .parse()
+                    .map_err(|error| warn!("skipping sprint: {}", error))
+                    .ok(),
+                SprintValue::Object(sprint) => Some(sprint),
+                SprintValue::Other(sprint) => {
+                    warn!("skipping invalid sprint: {}", sprint);
+                    None
+                }
+            })
+            .collect()
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{Duration, TimeZone, Utc};
@@ -49,10 +242,11 @@ mod tests {
         assert_eq!(sprint.id, 1001);
         assert_eq!(sprint.name, "Rust Sprint 1");
         assert_eq!(sprint.state, "closed");
-        assert_eq!(sprint.origin_board_id, 123);
+        assert_eq!(sprint.origin_board_id, Some(123));
         assert_eq!(sprint.start_date, None);
         assert_eq!(sprint.end_date, None);
         assert_eq!(sprint.complete_date, None);
+        assert_eq!(sprint.goal, None);
     }
 
     #[test]
@@ -62,6 +256,7 @@ mod tests {
             "state": "closed",
             "name": "Rust Sprint 1",
             "originBoardId": 123,
+            "goal": "Ship the sprint API",
             "completeDate": "2020-03-10T10:20:50.730Z",
             "endDate": "2020-03-02T22:01:00.000Z",
             "startDate": "2020-02-18T11:36:36.825Z"
@@ -72,30 +267,130 @@ mod tests {
         assert_eq!(sprint.id, 1001);
         assert_eq!(sprint.name, "Rust Sprint 1");
         assert_eq!(sprint.state, "closed");
-        assert_eq!(sprint.origin_board_id, 123);
+        assert_eq!(sprint.origin_board_id, Some(123));
         assert_eq!(
             sprint.start_date,
             Some(
-                Utc.with_ymd_and_hms(2020, 02, 18, 11, 36, 36).unwrap()
+                Utc.with_ymd_and_hms(2020, 2, 18, 11, 36, 36).unwrap()
                     + Duration::milliseconds(825)
             )
         );
 
         assert_eq!(
             sprint.end_date,
-            Some(
-                Utc.with_ymd_and_hms(2020, 03, 02, 22, 01, 00)
-                    .unwrap()
-                    .into()
-            )
+            Some(Utc.with_ymd_and_hms(2020, 3, 2, 22, 1, 0).unwrap())
         );
 
         assert_eq!(
             sprint.complete_date,
             Some(
-                Utc.with_ymd_and_hms(2020, 03, 10, 10, 20, 50).unwrap()
+                Utc.with_ymd_and_hms(2020, 3, 10, 10, 20, 50).unwrap()
                     + Duration::milliseconds(730)
             )
         );
+
+        assert_eq!(sprint.goal, Some("Ship the sprint API".to_owned()));
+    }
+
+    #[test]
+    fn update_serialization() {
+        let update = super::SprintUpdate {
+            state: Some(super::SprintState::Active),
+            start_date: Some(Utc.with_ymd_and_hms(2020, 2, 18, 9, 0, 0).unwrap()),
+            end_date: Some(Utc.with_ymd_and_hms(2020, 3, 3, 9, 0, 0).unwrap()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            json!({
+                "state": "active",
+                "startDate": "2020-02-18T09:00:00.000Z",
+                "endDate": "2020-03-03T09:00:00.000Z"
+            })
+        );
+    }
+
+    #[test]
+    fn legacy_string_parsing() {
+        let sprint: super::Sprint = "com.atlassian.greenhopper.service.sprint.Sprint@5fd6ab8e[id=1001,rapidViewId=123,state=CLOSED,name=Rust Sprint 1, part=2,goal=,startDate=2020-02-18T14:36:36.825+03:00,endDate=2020-03-03T01:01:00.000+03:00,completeDate=<null>,sequence=1001]"
+            .parse()
+            .unwrap();
+
+        assert_eq!(sprint.id, 1001);
+        assert_eq!(sprint.name, "Rust Sprint 1, part=2");
+        assert_eq!(sprint.state, "closed");
+        assert_eq!(sprint.origin_board_id, Some(123));
+        assert_eq!(
+            sprint.start_date,
+            Some(
+                Utc.with_ymd_and_hms(2020, 2, 18, 11, 36, 36).unwrap()
+                    + Duration::milliseconds(825)
+            )
+        );
+        assert_eq!(
+            sprint.end_date,
+            Some(Utc.with_ymd_and_hms(2020, 3, 2, 22, 1, 0).unwrap())
+        );
+        assert_eq!(sprint.complete_date, None);
+
+        assert!("Sprint@5fd6ab8e[name=No id]"
+            .parse::<super::Sprint>()
+            .is_err());
+    }
+
+    #[test]
+    fn sprint_field_parsing() {
+        #[derive(serde::Deserialize)]
+        struct Test {
+            #[serde(default, deserialize_with = "super::deserialize_optional_sprints")]
+            sprints: Option<Vec<super::Sprint>>,
+        }
+
+        let json = json!({
+            "sprints": [
+                "com.atlassian.greenhopper.service.sprint.Sprint@5fd6ab8e[id=1001,rapidViewId=123,state=ACTIVE,name=Rust Sprint 1,startDate=<null>,endDate=<null>,completeDate=<null>,sequence=1001]",
+                {
+                    "id": 1002,
+                    "name": "Rust Sprint 2",
+                    "state": "future",
+                    "boardId": 123,
+                    "goal": ""
+                }
+            ]
+        });
+
+        let test: Test = serde_json::from_value(json).unwrap();
+        let sprints = test.sprints.unwrap();
+
+        assert_eq!(sprints.len(), 2);
+        assert_eq!(sprints[0].id, 1001);
+        assert_eq!(sprints[0].state, "active");
+        assert_eq!(sprints[1].id, 1002);
+        assert_eq!(sprints[1].origin_board_id, Some(123));
+
+        // Sprints of deleted boards and entries that aren't sprints at all.
+        let json = json!({
+            "sprints": [
+                "com.atlassian.greenhopper.service.sprint.Sprint@5fd6ab8e[id=1003,rapidViewId=<null>,state=CLOSED,name=Orphan,startDate=<null>,endDate=<null>,completeDate=<null>,sequence=1003]",
+                {"id": 1004, "name": "Orphan 2", "state": "closed", "originBoardId": null},
+                "com.atlassian.greenhopper.service.sprint.Sprint@5fd6ab8e[id=<null>]",
+                {"name": "No id"},
+                42
+            ]
+        });
+
+        let test: Test = serde_json::from_value(json).unwrap();
+        let sprints = test.sprints.unwrap();
+
+        assert_eq!(sprints.len(), 2);
+        assert_eq!((sprints[0].id, sprints[0].origin_board_id), (1003, None));
+        assert_eq!((sprints[1].id, sprints[1].origin_board_id), (1004, None));
+
+        let test: Test = serde_json::from_value(json!({"sprints": null})).unwrap();
+        assert_eq!(test.sprints, None);
+
+        let test: Test = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(test.sprints, None);
     }
 }
@@ -1,5 +1,4 @@
-use chrono::NaiveDate;
-use chrono::{offset::TimeZone, Utc};
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Clone)]
@@ -11,7 +10,7 @@ pub struct TempoLog {
 use serde::{de, Deserializer};
 use std::fmt;
 
-const FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S%.3f";
+const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f";
 
 pub fn deserialize_date_time<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
 where
@@ -32,9 +31,9 @@ impl<'de> de::Visitor<'de> for DateTimeFromCustomFormatVisitor {
     where
         E: de::Error,
     {
-        Utc.datetime_from_str(&value, FORMAT)
+        NaiveDateTime::parse_from_str(value, FORMAT)
             .map_err(serde::de::Error::custom)
-            .map(|dt| dt.date_naive())
+            .map(|dt| dt.date())
     }
 }
 
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::issue::IssueStatus;
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Transition {
+    pub id: String,
+    pub name: String,
+    pub to: IssueStatus,
+
+    #[serde(default)]
+    pub fields: HashMap<String, TransitionField>,
+}
+
+impl Transition {
+    pub fn required_fields(&self) -> impl Iterator<Item = (&String, &TransitionField)> {
+        self.fields.iter().filter(|(_, field)| field.required)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionField {
+    pub required: bool,
+    pub name: String,
+
+    #[serde(default)]
+    pub allowed_values: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Transitions {
+    pub transitions: Vec<Transition>,
+}
+
+use std::fmt;
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    #[test]
+    fn parsing() {
+        let json = json!({
+            "expand": "transitions",
+            "transitions": [
+                {
+                    "id": "21",
+                    "name": "Start Progress",
+                    "to": {
+                        "self": "https://jira.example.io/rest/api/2/status/3",
+                        "name": "In Progress",
+                        "id": "3",
+                        "statusCategory": {"id": 4, "key": "indeterminate"}
+                    }
+                },
+                {
+                    "id": "31",
+                    "name": "Resolve",
+                    "to": {"name": "Resolved", "id": "5"},
+                    "fields": {
+                        "resolution": {
+                            "required": true,
+                            "name": "Resolution",
+                            "schema": {"type": "resolution", "system": "resolution"},
+                            "operations": ["set"],
+                            "allowedValues": [{"id": "1", "name": "Fixed"}]
+                        },
+                        "comment": {
+                            "required": false,
+                            "name": "Comment",
+                            "operations": ["add"]
+                        }
+                    }
+                }
+            ]
+        });
+
+        let transitions: super::Transitions = serde_json::from_value(json).unwrap();
+        let transitions = transitions.transitions;
+
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].id, "21");
+        assert_eq!(transitions[0].to.name, "In Progress");
+        assert!(transitions[0].fields.is_empty());
+
+        let required = transitions[1].required_fields().collect::<Vec<_>>();
+        assert_eq!(required.len(), 1);
+        assert_eq!(required[0].0, "resolution");
+        assert_eq!(required[0].1.name, "Resolution");
+        assert_eq!(required[0].1.allowed_values.as_ref().map(Vec::len), Some(1));
+    }
+}
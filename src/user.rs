@@ -1,16 +1,50 @@
 use serde::Deserialize;
 use std::hash::{Hash, Hasher};
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+/// Jira Server identifies users by `key` and `name`, Jira Cloud only returns `accountId`.
+///
+/// Users are equal when their `id()` is, and sorted by display name.
+#[derive(Clone, Debug, Deserialize)]
 pub struct User {
-    pub key: String,
-    pub name: String,
+    pub key: Option<String>,
+    pub name: Option<String>,
+    #[serde(rename = "accountId")]
+    pub account_id: Option<String>,
     #[serde(rename = "displayName")]
     pub display_name: Option<String>,
     #[serde(default)]
     pub groups: Groups,
 }
 
+impl User {
+    /// The most stable identifier available: account id, then key, then name.
+    pub fn id(&self) -> Option<UserId> {
+        self.account_id
+            .clone()
+            .map(UserId::AccountId)
+            .or_else(|| self.key.clone().map(UserId::Key))
+            .or_else(|| self.name.clone().map(UserId::Name))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UserId {
+    Key(String),
+    Name(String),
+    AccountId(String),
+}
+
+impl UserId {
+    /// Query parameter selecting this user in `/user` and similar endpoints.
+    pub(crate) fn param(&self) -> (&'static str, &str) {
+        match self {
+            UserId::Key(key) => ("key", key),
+            UserId::Name(name) => ("username", name),
+            UserId::AccountId(account_id) => ("accountId", account_id),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 pub struct Groups {
     pub size: usize,
@@ -25,26 +59,43 @@ pub struct Group {
 use std::fmt;
 impl fmt::Display for User {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.display_name.as_ref().unwrap_or(&self.name))
+        let name = self
+            .display_name
+            .as_ref()
+            .or(self.name.as_ref())
+            .or(self.account_id.as_ref())
+            .or(self.key.as_ref());
+
+        write!(f, "{}", name.map(String::as_str).unwrap_or_default())
     }
 }
 
 use std::cmp::Ordering;
 impl PartialOrd for User {
     fn partial_cmp(&self, other: &User) -> Option<Ordering> {
-        Some(self.cmp(&other))
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for User {
     fn cmp(&self, other: &User) -> Ordering {
-        self.display_name.cmp(&other.display_name)
+        self.display_name
+            .cmp(&other.display_name)
+            .then_with(|| self.id().cmp(&other.id()))
     }
 }
 
+impl PartialEq for User {
+    fn eq(&self, other: &User) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for User {}
+
 impl Hash for User {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.name.hash(state);
+        self.id().hash(state);
     }
 }
 
@@ -86,8 +137,9 @@ mod tests {
 
         let issue: super::User = serde_json::from_value(json).unwrap();
 
-        assert_eq!(issue.name, "chipp");
+        assert_eq!(issue.name, Some("chipp".to_owned()));
         assert_eq!(issue.display_name, Some("Vladimir Burdukov".to_owned()));
+        assert_eq!(issue.id(), Some(super::UserId::Key("chipp".to_owned())));
     }
 
     #[test]
@@ -96,7 +148,56 @@ mod tests {
 
         let issue: super::User = serde_json::from_value(json).unwrap();
 
-        assert_eq!(issue.name, "karumuga");
+        assert_eq!(issue.name, Some("karumuga".to_owned()));
         assert_eq!(issue.display_name, None);
+        assert_eq!(issue.to_string(), "karumuga");
+    }
+
+    #[test]
+    fn cloud_model_parsing() {
+        let json = json!({
+            "self": "https://example.atlassian.net/rest/api/3/user?accountId=5b10ac8d82e05b22cc7d4ef5",
+            "accountId": "5b10ac8d82e05b22cc7d4ef5",
+            "accountType": "atlassian",
+            "displayName": "Vladimir Burdukov",
+            "active": true,
+            "timeZone": "Europe/Vilnius"
+        });
+
+        let user: super::User = serde_json::from_value(json).unwrap();
+
+        assert_eq!(user.key, None);
+        assert_eq!(user.name, None);
+        assert_eq!(
+            user.id(),
+            Some(super::UserId::AccountId(
+                "5b10ac8d82e05b22cc7d4ef5".to_owned()
+            ))
+        );
+        assert_eq!(user.to_string(), "Vladimir Burdukov");
+    }
+
+    #[test]
+    fn identity_and_order() {
+        let user = |key: &str, display_name: &str| -> super::User {
+            serde_json::from_value(json!({"key": key, "displayName": display_name})).unwrap()
+        };
+
+        let renamed = user("chipp", "Vladimir");
+        assert_eq!(user("chipp", "Vladimir Burdukov"), renamed);
+
+        let mut users = [
+            user("b", "Same Name"),
+            user("a", "Same Name"),
+            user("c", "Another Name"),
+        ];
+        users.sort();
+
+        let keys = users
+            .iter()
+            .map(|user| user.key.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, [Some("c"), Some("a"), Some("b")]);
+        assert_ne!(users[1], users[2]);
     }
 }
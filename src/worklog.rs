@@ -1,7 +1,9 @@
+use super::error::ErrorCollection;
 use super::user::User;
 
-use chrono::NaiveDate;
-use serde::Deserialize;
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Worklogs {
@@ -18,30 +20,212 @@ pub struct Worklogs {
 #[derive(Deserialize, Debug, Clone)]
 pub struct Worklog {
     pub id: String,
+    /// Needed to attribute worklogs from `Client::get_worklogs_by_ids`.
+    #[serde(rename = "issueId")]
+    pub issue_id: Option<String>,
     pub author: User,
+    #[serde(rename = "updateAuthor")]
+    pub update_author: Option<User>,
+    #[serde(deserialize_with = "crate::adf::deserialize_optional_text", default)]
+    pub comment: Option<String>,
     #[serde(rename = "timeSpentSeconds")]
     pub time_spent: u32,
-    #[serde(rename = "started", with = "jira_datetime_format")]
-    pub date_started: NaiveDate,
+    /// In the offset it was logged with.
+    #[serde(with = "jira_datetime_format")]
+    pub started: DateTime<FixedOffset>,
+    #[serde(with = "jira_datetime_format::optional", default)]
+    pub created: Option<DateTime<FixedOffset>>,
+    #[serde(with = "jira_datetime_format::optional", default)]
+    pub updated: Option<DateTime<FixedOffset>>,
+}
+
+impl Worklog {
+    /// The day the work started on, in the offset it was logged with.
+    pub fn date_started(&self) -> NaiveDate {
+        self.started.date_naive()
+    }
 }
 
 mod jira_datetime_format {
-    use chrono::{DateTime, NaiveDate};
+    use chrono::{DateTime, FixedOffset};
     use serde::{self, Deserialize, Deserializer};
 
-    const FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S%.3f%z";
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%z";
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<FixedOffset>, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        DateTime::parse_from_str(&s, FORMAT)
-            .map(|datetime| datetime.date_naive())
-            .map_err(serde::de::Error::custom)
+        DateTime::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)
+    }
+
+    pub mod optional {
+        use chrono::{DateTime, FixedOffset};
+        use serde::{Deserialize, Deserializer};
+
+        pub fn deserialize<'de, D>(
+            deserializer: D,
+        ) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            match Option::<String>::deserialize(deserializer)? {
+                Some(s) => DateTime::parse_from_str(&s, super::FORMAT)
+                    .map(Some)
+                    .map_err(serde::de::Error::custom),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+/// Where an incremental worklog sync continues, see `Client::sync_worklogs`.
+///
+/// Serializable so it can be stored between runs, the default starts from
+/// the very first worklog.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorklogCursor {
+    // Milliseconds since the Unix epoch, as Jira expects them. The updated
+    // and deleted feeds are paged independently.
+    updated: i64,
+    deleted: i64,
+}
+
+impl WorklogCursor {
+    pub fn new(since: DateTime<Utc>) -> WorklogCursor {
+        let since = since.timestamp_millis();
+
+        WorklogCursor {
+            updated: since,
+            deleted: since,
+        }
+    }
+
+    /// The earliest change the next sync may return.
+    pub fn since(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.updated.min(self.deleted)).unwrap_or_default()
+    }
+
+    pub(crate) fn updated(&self) -> i64 {
+        self.updated
+    }
+
+    pub(crate) fn deleted(&self) -> i64 {
+        self.deleted
+    }
+
+    pub(crate) fn advance(
+        self,
+        updated: &WorklogChangePage,
+        deleted: &WorklogChangePage,
+    ) -> WorklogCursor {
+        WorklogCursor {
+            updated: updated.until.max(self.updated),
+            deleted: deleted.until.max(self.deleted),
+        }
     }
 }
 
+/// A batch of changed worklogs and the cursor for the next one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorklogChanges {
+    pub updated: Vec<Worklog>,
+    /// Ids of deleted worklogs, the numeric form of `Worklog::id`.
+    pub deleted: Vec<u64>,
+    pub cursor: WorklogCursor,
+    /// Whether Jira has more changes than fit in this batch.
+    pub has_more: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorklogChangePage {
+    pub(crate) values: Vec<WorklogChange>,
+    pub(crate) until: i64,
+    pub(crate) last_page: bool,
+}
+
+impl WorklogChangePage {
+    /// A page followed by more has to end after `since`, otherwise syncing
+    /// would request the same page forever.
+    pub(crate) fn is_stalled(&self, since: i64) -> bool {
+        !self.last_page && self.until <= since
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorklogChange {
+    pub(crate) worklog_id: u64,
+}
+
+/// How the remaining estimate of the issue changes with a worklog.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AdjustEstimate {
+    /// Reduced by the time spent, or increased back when deleting.
+    #[default]
+    Auto,
+    /// Left as is.
+    Leave,
+    /// Set to a new estimate, e.g. `2d 4h`.
+    New(String),
+    /// Reduced by an amount when adding, increased when deleting, e.g. `3h`.
+    /// Not available when updating a worklog.
+    Manual(String),
+}
+
+impl AdjustEstimate {
+    /// Query parameters, `manual` is `reduceBy` or `increaseBy`.
+    pub(crate) fn params(&self, manual: &'static str) -> Vec<(&'static str, String)> {
+        match self {
+            AdjustEstimate::Auto => vec![("adjustEstimate", "auto".to_owned())],
+            AdjustEstimate::Leave => vec![("adjustEstimate", "leave".to_owned())],
+            AdjustEstimate::New(estimate) => vec![
+                ("adjustEstimate", "new".to_owned()),
+                ("newEstimate", estimate.clone()),
+            ],
+            AdjustEstimate::Manual(amount) => vec![
+                ("adjustEstimate", "manual".to_owned()),
+                (manual, amount.clone()),
+            ],
+        }
+    }
+
+    /// Query parameters for updating a worklog, Jira rejects `Manual` there.
+    pub(crate) fn update_params(&self) -> Result<Vec<(&'static str, String)>, ErrorCollection> {
+        if let AdjustEstimate::Manual(_) = self {
+            let mut errors = ErrorCollection::default();
+            errors.errors.insert(
+                "adjustEstimate".to_owned(),
+                "manual adjustment isn't supported when updating a worklog".to_owned(),
+            );
+
+            return Err(errors);
+        }
+
+        Ok(self.params("reduceBy"))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorklogBody {
+    pub(crate) started: String,
+    pub(crate) time_spent_seconds: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) comment: Option<serde_json::Value>,
+}
+
+/// Formats `started` the way Jira expects it, e.g. `2019-03-11T09:00:00.000+0100`.
+pub(crate) fn format_started<Tz>(started: &DateTime<Tz>) -> String
+where
+    Tz: TimeZone,
+    Tz::Offset: fmt::Display,
+{
+    started.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string()
+}
+
 use std::hash::{Hash, Hasher};
 
 impl Hash for Worklog {
@@ -82,8 +266,125 @@ mod tests {
         assert_eq!(worklog.time_spent, 3600);
         assert_eq!(worklog.id, "1");
         assert_eq!(
-            worklog.date_started,
+            worklog.date_started(),
+            NaiveDate::from_ymd_opt(2019, 3, 11).unwrap()
+        );
+        assert_eq!(worklog.started.offset().local_minus_utc(), -5 * 3600);
+        assert!(worklog.comment.is_none());
+        assert!(worklog.created.is_none());
+    }
+
+    #[test]
+    fn full_parsing() {
+        let json = json!({
+            "id": "100028",
+            "issueId": "10010",
+            "author": {"key": "JIRAUSER1", "name": "vpupkin"},
+            "updateAuthor": {"key": "JIRAUSER2", "name": "ipetrov"},
+            "comment": "Reviewed the design",
+            "created": "2019-03-12T09:15:00.000+0300",
+            "updated": "2019-03-12T10:00:00.000+0300",
+            "started": "2019-03-11T23:30:00.000-0500",
+            "timeSpentSeconds": 5400
+        });
+
+        let worklog: Worklog = serde_json::from_value(json).unwrap();
+
+        // Late in the evening in New York is the next day in UTC.
+        assert_eq!(
+            worklog.date_started(),
             NaiveDate::from_ymd_opt(2019, 3, 11).unwrap()
         );
+        assert_eq!(
+            worklog.started.naive_utc().date(),
+            NaiveDate::from_ymd_opt(2019, 3, 12).unwrap()
+        );
+        assert_eq!(worklog.issue_id.as_deref(), Some("10010"));
+        assert_eq!(worklog.comment.as_deref(), Some("Reviewed the design"));
+        assert_eq!(
+            worklog.update_author.and_then(|user| user.name).as_deref(),
+            Some("ipetrov")
+        );
+        assert_eq!(
+            worklog.updated.unwrap() - worklog.created.unwrap(),
+            chrono::Duration::minutes(45)
+        );
+    }
+
+    #[test]
+    fn adjust_estimate() {
+        assert_eq!(
+            AdjustEstimate::default().params("reduceBy"),
+            [("adjustEstimate", "auto".to_owned())]
+        );
+        assert_eq!(
+            AdjustEstimate::New("2d".to_owned()).params("reduceBy"),
+            [
+                ("adjustEstimate", "new".to_owned()),
+                ("newEstimate", "2d".to_owned())
+            ]
+        );
+        assert_eq!(
+            AdjustEstimate::Manual("3h".to_owned()).params("increaseBy"),
+            [
+                ("adjustEstimate", "manual".to_owned()),
+                ("increaseBy", "3h".to_owned())
+            ]
+        );
+        assert_eq!(
+            AdjustEstimate::Leave.update_params().unwrap(),
+            [("adjustEstimate", "leave".to_owned())]
+        );
+        assert!(matches!(
+            AdjustEstimate::Manual("3h".to_owned()).update_params(),
+            Err(errors) if errors.errors.contains_key("adjustEstimate")
+        ));
+
+        let started = chrono::FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2019, 3, 11, 9, 0, 0)
+            .unwrap();
+        assert_eq!(format_started(&started), "2019-03-11T09:00:00.000+0100");
+    }
+
+    #[test]
+    fn change_pages() {
+        let page: WorklogChangePage = serde_json::from_value(json!({
+            "values": [
+                {"worklogId": 103, "updatedTime": 1438013671562u64, "properties": []},
+                {"worklogId": 104, "updatedTime": 1438013693136u64, "properties": []}
+            ],
+            "since": 1438013671562u64,
+            "until": 1438013693136u64,
+            "self": "https://jira.example.io/rest/api/2/worklog/updated?since=1438013671562",
+            "nextPage": "https://jira.example.io/rest/api/2/worklog/updated?since=1438013693136",
+            "lastPage": false
+        }))
+        .unwrap();
+
+        let ids = page.values.iter().map(|change| change.worklog_id);
+        assert_eq!(ids.collect::<Vec<_>>(), [103, 104]);
+        assert!(!page.last_page);
+
+        assert!(!page.is_stalled(1438013671562));
+        assert!(page.is_stalled(1438013693136));
+
+        let deleted: WorklogChangePage = serde_json::from_value(json!({
+            "values": [],
+            "since": 1438013600000u64,
+            "until": 1438013600000u64,
+            "lastPage": true
+        }))
+        .unwrap();
+        assert!(!deleted.is_stalled(1438013600000));
+
+        let start = Utc.with_ymd_and_hms(2015, 7, 27, 16, 13, 20).unwrap();
+        let cursor = WorklogCursor::new(start).advance(&page, &deleted);
+
+        assert_eq!(cursor.since(), start);
+        assert_eq!(
+            serde_json::to_value(cursor).unwrap(),
+            json!({"updated": 1438013693136u64, "deleted": 1438013600000u64})
+        );
     }
 }